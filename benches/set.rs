@@ -0,0 +1,26 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn unconstrained(c: &mut Criterion) {
+    let now = || 0;
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    c.bench_function("bang unconstrained", |b| {
+        b.iter(|| black_box(on_off.bang()).is_ok())
+    });
+}
+
+fn constrained(c: &mut Criterion) {
+    let now = || 0;
+    let one_second = Duration::from_secs(1);
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, None, None, Some(one_second), Some(one_second), &now);
+
+    c.bench_function("bang constrained", |b| {
+        b.iter(|| black_box(on_off.bang()).is_err())
+    });
+}
+
+criterion_group!(benches, unconstrained, constrained);
+criterion_main!(benches);
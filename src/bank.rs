@@ -0,0 +1,42 @@
+//! fixed-size group of controllers supervised together
+
+use crate::TimeConstrainedOnOff;
+use bangbang::prelude::*;
+
+/// fixed number of time constrained controllers driven as one, such as the outputs of a
+/// multi-channel relay board
+#[derive(Debug)]
+pub struct ControllerBank<'a, const N: usize> {
+    controllers: [TimeConstrainedOnOff<'a>; N],
+}
+
+impl<'a, const N: usize> ControllerBank<'a, N> {
+    /// groups the provided controllers, which keep their position as their index in the bank
+    pub fn new(controllers: [TimeConstrainedOnOff<'a>; N]) -> Self {
+        Self { controllers }
+    }
+
+    /// the controllers of the bank
+    pub fn controllers(&self) -> &[TimeConstrainedOnOff<'a>; N] {
+        &self.controllers
+    }
+
+    /// mutable access to the controllers of the bank
+    pub fn controllers_mut(&mut self) -> &mut [TimeConstrainedOnOff<'a>; N] {
+        &mut self.controllers
+    }
+
+    /// calls [`TimeConstrainedOnOff::poll`] on every controller, reporting the state each one
+    /// was forced into by its maximum time, indexed by position in the bank
+    ///
+    /// Controllers that were not forced, including those without a maximum and those whose
+    /// handler vetoed the forced transition, report `None`.
+    pub fn poll_all(&mut self) -> [Option<BangBangState>; N] {
+        let mut forced = [None; N];
+        for (forced, controller) in forced.iter_mut().zip(self.controllers.iter_mut()) {
+            *forced = controller.poll().unwrap_or(None);
+        }
+
+        forced
+    }
+}
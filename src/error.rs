@@ -0,0 +1,212 @@
+//! errors reported by the time constrained controllers
+
+use bangbang::prelude::*;
+use core::convert::TryFrom;
+use core::time::Duration;
+
+/// reason a state transition did not take place
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum TransitionError {
+    /// the minimum time constraint of the current state has not yet been satisfied
+    Constrained {
+        /// state the controller remains in
+        from: BangBangState,
+        /// state that was requested
+        to: BangBangState,
+        /// time left until the constraint is satisfied, measured when the transition was blocked
+        remaining: Duration,
+    },
+    /// the minimum time constraint blocked the transition and the installed fallback chose
+    /// [`Decision::Abort`](crate::Decision::Abort)
+    Aborted {
+        /// state the controller remains in
+        from: BangBangState,
+        /// state that was requested
+        to: BangBangState,
+    },
+    /// the upstream controller of a staged transition had not yet been `on` for its minimum, see
+    /// [`TimeConstrainedOnOff::set_linked`](crate::TimeConstrainedOnOff::set_linked)
+    UpstreamNotReady {
+        /// state the controller remains in
+        from: BangBangState,
+        /// state that was requested
+        to: BangBangState,
+    },
+    /// the state change handler returned the contained error
+    Vetoed(BangBangError),
+    /// another controller of the same [`Interlock`](crate::interlock::Interlock) was `on`
+    Interlocked {
+        /// state the controller remains in
+        from: BangBangState,
+        /// state that was requested
+        to: BangBangState,
+    },
+}
+
+impl TransitionError {
+    /// time left until the transition would be permitted, only known when blocked by a time
+    /// constraint
+    pub fn remaining(&self) -> Option<Duration> {
+        match *self {
+            TransitionError::Constrained { remaining, .. } => Some(remaining),
+            _ => None,
+        }
+    }
+
+    /// whether the transition was blocked by a time constraint and may succeed if retried later
+    pub fn is_constrained(&self) -> bool {
+        matches!(self, TransitionError::Constrained { .. })
+    }
+
+    /// whether the state change handler refused the transition
+    pub fn is_vetoed(&self) -> bool {
+        matches!(self, TransitionError::Vetoed(_))
+    }
+
+    /// classification of the error without its details
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TransitionError::Constrained { .. } => ErrorKind::Constrained,
+            TransitionError::Aborted { .. } => ErrorKind::Aborted,
+            TransitionError::UpstreamNotReady { .. } => ErrorKind::UpstreamNotReady,
+            TransitionError::Vetoed(_) => ErrorKind::Vetoed,
+            TransitionError::Interlocked { .. } => ErrorKind::Interlocked,
+        }
+    }
+
+    /// stable numeric code of the error, see [`ErrorKind::code`]
+    pub fn code(&self) -> u16 {
+        self.kind().code()
+    }
+}
+
+/// classification of a [`TransitionError`], suitable for looking up a message per kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// see [`TransitionError::Constrained`]
+    Constrained,
+    /// see [`TransitionError::Aborted`]
+    Aborted,
+    /// see [`TransitionError::UpstreamNotReady`]
+    UpstreamNotReady,
+    /// see [`TransitionError::Vetoed`]
+    Vetoed,
+    /// see [`TransitionError::Interlocked`]
+    Interlocked,
+}
+
+impl ErrorKind {
+    /// every kind of error, in order of their codes
+    pub const ALL: [ErrorKind; 5] = [
+        ErrorKind::Constrained,
+        ErrorKind::Aborted,
+        ErrorKind::UpstreamNotReady,
+        ErrorKind::Vetoed,
+        ErrorKind::Interlocked,
+    ];
+
+    /// stable numeric code of the kind, which is never reused or changed once assigned
+    ///
+    /// | kind               | code |
+    /// |--------------------|------|
+    /// | `Constrained`      | 0    |
+    /// | `Aborted`          | 1    |
+    /// | `UpstreamNotReady` | 2    |
+    /// | `Vetoed`           | 3    |
+    /// | `Interlocked`      | 4    |
+    pub fn code(self) -> u16 {
+        match self {
+            ErrorKind::Constrained => 0,
+            ErrorKind::Aborted => 1,
+            ErrorKind::UpstreamNotReady => 2,
+            ErrorKind::Vetoed => 3,
+            ErrorKind::Interlocked => 4,
+        }
+    }
+}
+
+/// reason a request made through an [`Interlock`](crate::interlock::Interlock) was not carried out
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum InterlockError {
+    /// the interlock has no controller at the contained index
+    NoSuchController(usize),
+    /// a transition of one of the interlocked controllers failed with the contained error
+    Transition(TransitionError),
+}
+
+impl InterlockError {
+    /// the transition error, unless the request named a controller the interlock does not have
+    pub fn transition(&self) -> Option<&TransitionError> {
+        match self {
+            InterlockError::Transition(error) => Some(error),
+            InterlockError::NoSuchController(_) => None,
+        }
+    }
+}
+
+impl From<TransitionError> for InterlockError {
+    fn from(error: TransitionError) -> Self {
+        InterlockError::Transition(error)
+    }
+}
+
+/// internal inconsistency reported by [`TimeConstrainedOnOff::check_invariants`](crate::TimeConstrainedOnOff::check_invariants)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// the most recently recorded transition entered a different state than the current one
+    HistoryDisagreesWithState,
+    /// the state published to the state mirror differs from the current state
+    MirrorDisagreesWithState,
+    /// a time constraint exceeds the range of the `u32` millisecond clock
+    ConstraintOutOfRange,
+    /// an extended minimum `off` time is in effect while the controller is `on`
+    ExtendedMinimumWhileOn,
+    /// blocked transitions were counted towards stall detection without a record of the block
+    StallCountWithoutBlock,
+    /// the most recent transition was neither forced nor exempt from the minimum time
+    /// constraint, yet less time than the minimum had passed on the clock
+    MinimumBypassed,
+    /// the timer restarted at an earlier reading than it previously did, by more than a wrap of
+    /// the clock explains
+    LastChangedRegressed,
+    /// the transition count differs from the number of transitions recorded in the history since
+    /// the count started or was last restored
+    TransitionCountDisagreesWithHistory,
+    /// less time was accumulated in a state than the retained history shows was spent in it
+    TimeDisagreesWithHistory,
+}
+
+/// infeasible configuration reported by [`TimeConstrainedOnOff::validate`](crate::TimeConstrainedOnOff::validate)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// the minimum time in the contained state is longer than its maximum, so the maximum would
+    /// always force a transition before the minimum permits one
+    MinimumExceedsMaximum(BangBangState),
+    /// the factor given to
+    /// [`TimeConstrainedOnOff::with_proportional_off`](crate::TimeConstrainedOnOff::with_proportional_off)
+    /// is negative or not a number
+    InvalidProportionalFactor,
+    /// the minimum time in the contained state is longer than the `u32` millisecond clock can
+    /// measure
+    ExceedsClockRange(BangBangState),
+}
+
+/// errors become [`BangBangError::StateChangeTemporarilyConstrained`] with the
+/// [`TransitionError::code`] as `code`, while vetoes are passed through unchanged
+impl From<TransitionError> for BangBangError {
+    fn from(error: TransitionError) -> Self {
+        let code = u8::try_from(error.code()).unwrap_or(u8::MAX);
+        match error {
+            TransitionError::Constrained { from, to, .. }
+            | TransitionError::Aborted { from, to }
+            | TransitionError::UpstreamNotReady { from, to }
+            | TransitionError::Interlocked { from, to } => {
+                BangBangError::StateChangeTemporarilyConstrained { from, to, code }
+            }
+            TransitionError::Vetoed(error) => error,
+        }
+    }
+}
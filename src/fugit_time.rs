@@ -0,0 +1,54 @@
+//! constructors and accessors speaking [`fugit`] durations
+
+use crate::{saturating_millis, CurrentTimeMilliseconds, StateChangeHander, TimeConstrainedOnOff};
+use core::time::Duration;
+use fugit::MillisDurationU32;
+
+impl<'a> TimeConstrainedOnOff<'a> {
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] with minimum time
+    /// constraints given as [`fugit`] millisecond durations
+    pub fn new_fugit(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<MillisDurationU32>,
+        minimum_off: Option<MillisDurationU32>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        Self::new(
+            on,
+            handle_on,
+            handle_off,
+            minimum_on.map(from_fugit),
+            minimum_off.map(from_fugit),
+            now,
+        )
+    }
+
+    /// same as [`TimeConstrainedOnOff::minimum_on`] as a [`fugit`] duration
+    pub fn minimum_on_fugit(&self) -> Option<MillisDurationU32> {
+        self.minimum_on().map(to_fugit)
+    }
+
+    /// same as [`TimeConstrainedOnOff::minimum_off`] as a [`fugit`] duration
+    pub fn minimum_off_fugit(&self) -> Option<MillisDurationU32> {
+        self.minimum_off().map(to_fugit)
+    }
+
+    /// same as [`TimeConstrainedOnOff::time_remaining`] as a [`fugit`] duration
+    ///
+    /// Fractions of a millisecond are rounded up, so a blocked controller never reports zero.
+    #[must_use]
+    pub fn time_remaining_fugit(&self) -> Option<MillisDurationU32> {
+        self.time_remaining()
+            .map(|remaining| to_fugit(remaining.saturating_add(Duration::from_nanos(999_999))))
+    }
+}
+
+fn from_fugit(duration: MillisDurationU32) -> Duration {
+    Duration::from_millis(u64::from(duration.ticks()))
+}
+
+fn to_fugit(duration: Duration) -> MillisDurationU32 {
+    MillisDurationU32::from_ticks(saturating_millis(duration))
+}
@@ -0,0 +1,46 @@
+//! fixed-size record of the most recent state transitions
+
+use bangbang::prelude::*;
+
+/// number of transitions retained by each controller, older transitions are overwritten
+pub const HISTORY_CAPACITY: usize = 16;
+
+/// ring buffer of `(timestamp, new state)` pairs, one per successful transition
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct History {
+    entries: [(u32, BangBangState); HISTORY_CAPACITY],
+    len: usize,
+    next: usize,
+    recorded: u64,
+}
+
+impl History {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: [(0, BangBangState::A); HISTORY_CAPACITY],
+            len: 0,
+            next: 0,
+            recorded: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, timestamp: u32, state: BangBangState) {
+        self.entries[self.next] = (timestamp, state);
+        self.next = (self.next + 1) % HISTORY_CAPACITY;
+        if self.len < HISTORY_CAPACITY {
+            self.len += 1;
+        }
+        self.recorded = self.recorded.saturating_add(1);
+    }
+
+    /// transitions pushed since creation, including those since overwritten
+    pub(crate) fn recorded(&self) -> u64 {
+        self.recorded
+    }
+
+    /// recorded transitions, newest first
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = (u32, BangBangState)> + '_ {
+        (1..=self.len)
+            .map(move |age| self.entries[(self.next + HISTORY_CAPACITY - age) % HISTORY_CAPACITY])
+    }
+}
@@ -0,0 +1,139 @@
+//! setpoint controller that drives a [`TimeConstrainedOnOff`] from measurements using a deadband
+
+use crate::{TimeConstrainedOnOff, TransitionError};
+use bangbang::prelude::*;
+use core::time::Duration;
+
+#[cfg(feature = "log")]
+use log::trace;
+
+/// result of feeding a measurement to [`HysteresisOnOff::update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// the measurement reached the low threshold and the controller turned `on`
+    TurnedOn,
+    /// the measurement reached the high threshold and the controller turned `off`
+    TurnedOff,
+    /// the measurement is between the thresholds, so the state is held
+    InDeadband,
+    /// the measurement reached a threshold but the minimum time constraint of the current state
+    /// held back the transition
+    BlockedByMinimum {
+        /// time left until the constraint is satisfied
+        remaining: Duration,
+    },
+    /// the measurement reached the threshold of the state the controller is already in
+    Unchanged,
+    /// the measurement was `NaN` or infinite and was discarded, leaving the filter and the state
+    /// unchanged
+    NotFinite,
+}
+
+/// on/off controller that turns on at or below a low threshold and off at or above a high threshold
+///
+/// Measurements between the two thresholds (the deadband) leave the state unchanged, which is the
+/// classic thermostat arrangement. The time constraints of the wrapped controller still apply.
+#[derive(Debug)]
+pub struct HysteresisOnOff<'a> {
+    controller: TimeConstrainedOnOff<'a>,
+    low: f32,
+    high: f32,
+    alpha: Option<f32>,
+    filtered: Option<f32>,
+}
+
+impl<'a> HysteresisOnOff<'a> {
+    /// creates a new setpoint controller turning on at or below `low` and off at or above `high`
+    ///
+    /// Debug builds panic if `low` is above `high` or either is `NaN`, as measurements between
+    /// inverted thresholds would reach both and the controller could never turn `off`.
+    pub fn new(controller: TimeConstrainedOnOff<'a>, low: f32, high: f32) -> Self {
+        debug_assert!(
+            low <= high,
+            "low threshold of {} must not be above the high threshold of {}",
+            low,
+            high
+        );
+
+        Self {
+            controller,
+            low,
+            high,
+            alpha: None,
+            filtered: None,
+        }
+    }
+
+    /// applies an exponentially weighted moving average to measurements before the threshold
+    /// comparison, where `alpha` in `(0.0, 1.0]` is the weight given to each new measurement
+    ///
+    /// The filter keeps a single running value, so it needs no buffer on `no_std` targets. Lower
+    /// values of `alpha` smooth out more noise at the cost of reacting more slowly. An `alpha` of
+    /// `NaN` is treated as `1.0`, leaving measurements unfiltered.
+    pub fn with_filter(mut self, alpha: f32) -> Self {
+        let alpha = if alpha.is_nan() { 1.0 } else { alpha };
+        self.alpha = Some(alpha.clamp(f32::MIN_POSITIVE, 1.0));
+        self
+    }
+
+    /// feeds a new measurement, transitioning the wrapped controller when a threshold is crossed,
+    /// and reports why the state did or did not change
+    ///
+    /// A transition held back by a minimum time constraint is reported as
+    /// [`UpdateOutcome::BlockedByMinimum`] rather than as an error, errors are left for vetoes.
+    /// A measurement that is `NaN` or infinite would stay in the filter for good, so it is
+    /// discarded and reported as [`UpdateOutcome::NotFinite`].
+    pub fn update(&mut self, measurement: f32) -> Result<UpdateOutcome, BangBangError> {
+        if !measurement.is_finite() {
+            return Ok(UpdateOutcome::NotFinite);
+        }
+        let measurement = self.filter(measurement);
+
+        let (new_state, outcome) = if measurement <= self.low {
+            (BangBangState::B, UpdateOutcome::TurnedOn)
+        } else if measurement >= self.high {
+            (BangBangState::A, UpdateOutcome::TurnedOff)
+        } else {
+            return Ok(UpdateOutcome::InDeadband);
+        };
+        if self.controller.state() == new_state {
+            return Ok(UpdateOutcome::Unchanged);
+        }
+
+        match self.controller.try_set(new_state) {
+            Ok(()) => Ok(outcome),
+            Err(TransitionError::Constrained { remaining, .. }) => {
+                Ok(UpdateOutcome::BlockedByMinimum { remaining })
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// most recent measurement after filtering, if any measurement has been fed
+    pub fn filtered(&self) -> Option<f32> {
+        self.filtered
+    }
+
+    /// the wrapped time constrained controller
+    pub fn controller(&self) -> &TimeConstrainedOnOff<'a> {
+        &self.controller
+    }
+
+    /// mutable access to the wrapped time constrained controller
+    pub fn controller_mut(&mut self) -> &mut TimeConstrainedOnOff<'a> {
+        &mut self.controller
+    }
+
+    fn filter(&mut self, measurement: f32) -> f32 {
+        let filtered = match (self.alpha, self.filtered) {
+            (Some(alpha), Some(previous)) => previous + alpha * (measurement - previous),
+            _ => measurement,
+        };
+
+        #[cfg(feature = "log")]
+        trace!("measurement {} filtered to {}", measurement, filtered);
+
+        self.filtered = Some(filtered);
+        filtered
+    }
+}
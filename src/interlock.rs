@@ -0,0 +1,121 @@
+//! mutual exclusion between controllers that must never be `on` at the same time
+
+use crate::{InterlockError, TimeConstrainedOnOff, TransitionError};
+use bangbang::prelude::*;
+
+/// what [`Interlock::request_on`] does when another controller of the interlock is `on`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterlockPolicy {
+    /// refuse the request, reported as [`TransitionError::Interlocked`] with the state of the
+    /// requested controller (default)
+    #[default]
+    Refuse,
+    /// turn the other controllers `off` first, subject to their own time constraints and
+    /// handlers, and only turn the requested one `on` once all of them are `off`
+    SwitchOthersOff,
+}
+
+/// group of controllers of which at most one may be `on`, such as a heater and a cooler
+///
+/// Only transitions requested through the interlock are checked, controllers changed directly
+/// or forced by their maximums are not.
+#[derive(Debug)]
+pub struct Interlock<'r, 'a, const N: usize> {
+    controllers: [&'r mut TimeConstrainedOnOff<'a>; N],
+    policy: InterlockPolicy,
+}
+
+impl<'r, 'a, const N: usize> Interlock<'r, 'a, N> {
+    /// interlocks the provided controllers, which keep their position as their index
+    pub fn new(controllers: [&'r mut TimeConstrainedOnOff<'a>; N]) -> Self {
+        Self {
+            controllers,
+            policy: InterlockPolicy::default(),
+        }
+    }
+
+    /// changes what happens when another controller is `on` as one is requested `on`
+    pub fn with_policy(mut self, policy: InterlockPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// turns the controller at `index` `on` if every other controller is `off`, switching them
+    /// `off` first if the policy permits
+    ///
+    /// When switching others `off`, the time constraints of the requested controller and of every
+    /// controller to be switched `off` are checked before any of them changes, so a blocked
+    /// request leaves them all as they were. A handler veto can only be known by attempting the
+    /// transition, so it stops the request with its error, leaving any already switched `off` as
+    /// they are.
+    pub fn request_on(&mut self, index: usize) -> Result<(), InterlockError> {
+        let target = self
+            .controllers
+            .get(index)
+            .ok_or(InterlockError::NoSuchController(index))?;
+        let from = target.state();
+        let others_on = self
+            .controllers
+            .iter()
+            .enumerate()
+            .any(|(other, controller)| other != index && controller.is_on());
+
+        if !others_on {
+            return Ok(self.controllers[index].try_set(BangBangState::B)?);
+        }
+
+        if self.policy == InterlockPolicy::Refuse {
+            return Err(TransitionError::Interlocked {
+                from,
+                to: BangBangState::B,
+            }
+            .into());
+        }
+
+        if from == BangBangState::A {
+            Self::check_permitted(target, BangBangState::B)?;
+        }
+        for (other, controller) in self.controllers.iter().enumerate() {
+            if other != index && controller.is_on() {
+                Self::check_permitted(controller, BangBangState::A)?;
+            }
+        }
+
+        for (other, controller) in self.controllers.iter_mut().enumerate() {
+            if other != index && controller.is_on() {
+                controller.try_set(BangBangState::A)?;
+            }
+        }
+
+        Ok(self.controllers[index].try_set(BangBangState::B)?)
+    }
+
+    /// turns the controller at `index` `off`, which the interlock never refuses
+    pub fn request_off(&mut self, index: usize) -> Result<(), InterlockError> {
+        let controller = self
+            .controllers
+            .get_mut(index)
+            .ok_or(InterlockError::NoSuchController(index))?;
+
+        Ok(controller.try_set(BangBangState::A)?)
+    }
+
+    fn check_permitted(
+        controller: &TimeConstrainedOnOff<'_>,
+        to: BangBangState,
+    ) -> Result<(), TransitionError> {
+        match controller.time_remaining() {
+            Some(remaining) => Err(TransitionError::Constrained {
+                from: controller.state(),
+                to,
+                remaining,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// the interlocked controllers
+    pub fn controllers(&self) -> &[&'r mut TimeConstrainedOnOff<'a>; N] {
+        &self.controllers
+    }
+}
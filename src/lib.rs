@@ -83,6 +83,32 @@
 //! | --- | --- | --- |
 //! | log | enabled | enables the [`log`] crate dependency and logging calls |
 //! | all_log | enabled | enables the `log` feature locally as well as in dependencies |
+//! | std | disabled | enables the [`std_clock`] module and the std-only constructors |
+//! | heapless | disabled | enables the [`heapless`] crate dependency and the [`queue`] module |
+//! | async | disabled | enables the [`timer`] module for waiting out time constraints asynchronously |
+//! | fugit | disabled | enables the [`fugit`] crate dependency and constructors and accessors using its durations |
+//! | debug-strict | disabled | asserts in debug builds that no transition bypasses an active minimum unless forced |
+//! | metrics | disabled | enables the [`metrics`] crate dependency and [`TimeConstrainedOnOff::record_metrics`] |
+//!
+//! # Code Size
+//!
+//! Every logging call site, along with the formatting of its arguments, is compiled out when the
+//! `log` feature is disabled, leaving the [`Debug`](core::fmt::Debug) implementations as the only
+//! formatting code, which the linker discards unless the application uses them. For the smallest
+//! footprint disable the default features and build the application with a size optimized
+//! profile, for example:
+//!
+//! ```toml,ignore
+//! [dependencies.bangbang_timed]
+//! version = "0.1.0"
+//! default-features = false
+//!
+//! [profile.release]
+//! opt-level = "z"
+//! lto = true
+//! codegen-units = 1
+//! panic = "abort"
+//! ```
 #![no_std]
 #![deny(warnings)]
 #![deny(bad_style)]
@@ -108,34 +134,182 @@
 #![deny(variant_size_differences)]
 #![cfg_attr(feature = "cargo-clippy", deny(clippy::all))]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 use bangbang::prelude::*;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
 use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::sync::atomic::AtomicBool;
 use core::time::Duration;
 
 #[cfg(feature = "log")]
-use log::{debug, trace, warn};
+use log::{debug, error, trace, warn};
+
+pub mod bank;
+mod error;
+#[cfg(feature = "fugit")]
+mod fugit_time;
+mod history;
+pub mod hysteresis;
+pub mod interlock;
+pub mod monotonic;
+#[cfg(feature = "heapless")]
+pub mod queue;
+mod reader;
+pub mod source;
+mod status;
+#[cfg(feature = "std")]
+pub mod std_clock;
+mod telemetry;
+#[cfg(feature = "async")]
+pub mod timer;
+
+pub use error::{ConfigError, ErrorKind, InterlockError, InvariantViolation, TransitionError};
+use history::History;
+pub use history::HISTORY_CAPACITY;
+pub use reader::StateReader;
+use source::CommandSource;
+pub use status::StatusFlags;
+pub use telemetry::Metrics;
+
+/// unit of the readings returned by the `now` method and of the timestamps taken by the `_at`
+/// methods
+pub const TIME_UNIT: &str = "milliseconds";
 
 /// handler method to be called on a state change
 type StateChangeHander = dyn FnMut() -> Result<(), BangBangError> + Sync + Send;
 
+/// handler method to be called on a state change in either direction with the new state
+type UnifiedStateChangeHandler =
+    dyn FnMut(BangBangState) -> Result<(), BangBangError> + Sync + Send;
+
+/// handler method to be called when a transition to the contained state becomes permitted
+type ReadyCallback = dyn FnMut(BangBangState) + Sync + Send;
+
+/// side-effect free method predicting whether the handler would veto a transition to the
+/// contained state
+type VetoPredicate = dyn Fn(BangBangState) -> bool + Sync;
+
+/// condition under which the minimum time constraints are enforced
+type MinimumCondition = dyn Fn() -> bool + Sync;
+
 /// handler method to be called when the current time in milliseconds is required
 type CurrentTimeMilliseconds = dyn Fn() -> u32 + Sync;
 
+/// handler method to be consulted when a time constraint blocks a state transition
+type ConstraintFallback = dyn FnMut(&TimeConstrainedOnOff<'_>) -> Decision + Sync + Send;
+
 /// A convenience module appropriate for glob imports (`use bangbang_timed::prelude::*;`)
+///
+/// Items that need the standard library are only included when the `std` feature is enabled.
 pub mod prelude {
     #[doc(no_inline)]
-    pub use super::TimeConstrainedOnOff;
+    pub use super::bank::ControllerBank;
+    #[doc(no_inline)]
+    pub use super::hysteresis::{HysteresisOnOff, UpdateOutcome};
+    #[doc(no_inline)]
+    pub use super::interlock::{Interlock, InterlockPolicy};
+    #[doc(no_inline)]
+    pub use super::monotonic::{InstantOnOff, MonotonicClock};
+    #[cfg(feature = "heapless")]
+    #[doc(no_inline)]
+    pub use super::queue::{CommandConsumer, CommandProducer, CommandQueue};
+    #[doc(no_inline)]
+    pub use super::source::{CommandSource, PeriodicToggle, Replay, ThresholdSource};
+    #[cfg(feature = "std")]
+    #[doc(no_inline)]
+    pub use super::std_clock::now_ms;
+    #[cfg(feature = "async")]
+    #[doc(no_inline)]
+    pub use super::timer::{AsyncOnOff, Timer};
+    #[doc(no_inline)]
+    pub use super::{
+        soonest_allowed, unpack_status, BoundaryPolicy, ClockPolicy, Command, Config, ConfigError,
+        Decision, ErrorKind, HandlerChain, InterlockError, InvariantViolation, Metrics, SimResult,
+        StateReader, StatusFlags, StepReport, TimeConstrainedOnOff, TimerResetPolicy,
+        TransitionError, Tristate,
+    };
     #[doc(no_inline)]
     pub use bangbang::prelude::*;
 }
 
 /// on/off bang-bang controller that restricts how quickly states can be changed
+///
+/// Methods that only report on the controller, such as [`TimeConstrainedOnOff::is_on`],
+/// [`BangBang::state`], [`TimeConstrainedOnOff::time_remaining`] and
+/// [`TimeConstrainedOnOff::time_in_state`], take `&self` and have no interior mutability, so they
+/// can be called through a shared reference without a `RefCell`. At most they read the clock.
+/// Anything that changes state or statistics, including blocked transitions, takes `&mut self`.
+///
+/// Handlers are called while the controller is mutably borrowed and must not borrow anything
+/// shorter lived than `'static`, so a handler can never reach the controller it is installed on
+/// to call back into it. Re-entrant transitions are rejected at compile time, and sharing the
+/// controller through a `Mutex` or `RefCell` turns an attempt into a failed lock or borrow
+/// rather than a corrupted state.
 pub struct TimeConstrainedOnOff<'a> {
     bang_bang: OnOff<'a>,
+    handle_on: Option<&'a mut StateChangeHander>,
+    handle_off: Option<&'a mut StateChangeHander>,
+    unified_handler: Option<&'a mut UnifiedStateChangeHandler>,
     minimum_on: Option<Duration>,
     minimum_off: Option<Duration>,
+    maximum_on: Option<Duration>,
+    maximum_off: Option<Duration>,
     last_changed: u32,
+    primed: u32,
     now: &'a CurrentTimeMilliseconds,
+    off_clock: Option<&'a CurrentTimeMilliseconds>,
+    fallback: Option<&'a mut ConstraintFallback>,
+    clock_policy: ClockPolicy,
+    history: History,
+    last_blocked_at: Option<u32>,
+    was_ready: bool,
+    constraints_suspended: bool,
+    proportional_off: Option<f32>,
+    dynamic_minimum_off: Option<Duration>,
+    #[cfg(feature = "log")]
+    log_timing: bool,
+    stall_threshold: Option<u32>,
+    identical_blocked_readings: u32,
+    blocked_attempts: u32,
+    reader: Option<StateReader<'a>>,
+    handler_timing: bool,
+    last_on_handler_duration: Option<Duration>,
+    last_off_handler_duration: Option<Duration>,
+    last_error: Option<TransitionError>,
+    tolerance: Duration,
+    handler_is_advisory: bool,
+    on_ready: Option<&'a mut ReadyCallback>,
+    gated: bool,
+    active_time: u32,
+    veto_predicate: Option<&'a VetoPredicate>,
+    time_on: u64,
+    time_off: u64,
+    checkpoint: (u64, u64),
+    restored_transitions: (u64, u64),
+    backward_jumps: u32,
+    last_reading: Option<u32>,
+    backward_jump_threshold: Option<u32>,
+    timer_reset_policy: TimerResetPolicy,
+    entered_at: u32,
+    started: bool,
+    timing_hysteresis: Duration,
+    boundary_raised: bool,
+    settling: Duration,
+    minimum_condition: Option<&'a MinimumCondition>,
+    known: bool,
+    unscaled_minimums: Option<(Option<Duration>, Option<Duration>)>,
+    transitions: u64,
+    boundary_policy: BoundaryPolicy,
+    pending: Option<Command>,
+    last_on_at: Option<u32>,
+    last_off_at: Option<u32>,
+    minimum_bypassed: bool,
+    previous_changed: Option<u32>,
+    wraps_onoff: bool,
 }
 
 impl fmt::Debug for TimeConstrainedOnOff<'_> {
@@ -148,38 +322,256 @@ impl fmt::Debug for TimeConstrainedOnOff<'_> {
     }
 }
 
+/// compares the state, minimum time constraints and time of the last transition, ignoring the
+/// handlers, clocks, fallback and all other configuration, consistent with the [`Hash`] impl
+impl PartialEq for TimeConstrainedOnOff<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_on() == other.is_on()
+            && self.minimum_on == other.minimum_on
+            && self.minimum_off == other.minimum_off
+            && self.last_changed == other.last_changed
+    }
+}
+
+impl Eq for TimeConstrainedOnOff<'_> {}
+
+/// hashes the state, minimum time constraints and time of the last transition, the handlers,
+/// clocks and fallback are closures that cannot be hashed and are excluded along with all other
+/// configuration
+impl Hash for TimeConstrainedOnOff<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.is_on().hash(state);
+        self.minimum_on.hash(state);
+        self.minimum_off.hash(state);
+        self.last_changed.hash(state);
+    }
+}
+
+/// starts in the `off` state with no handlers and no time constraints, measuring time with
+/// [`std_clock::now_ms`]
+#[cfg(feature = "std")]
+impl Default for TimeConstrainedOnOff<'_> {
+    fn default() -> Self {
+        Self::new_std(false, None, None, None, None)
+    }
+}
+
+/// creates a controller without handlers from `(on, minimum_on, minimum_off, now)` as
+/// [`TimeConstrainedOnOff::try_new`] does
+impl<'a>
+    TryFrom<(
+        bool,
+        Option<Duration>,
+        Option<Duration>,
+        &'a CurrentTimeMilliseconds,
+    )> for TimeConstrainedOnOff<'a>
+{
+    type Error = ConfigError;
+
+    fn try_from(
+        (on, minimum_on, minimum_off, now): (
+            bool,
+            Option<Duration>,
+            Option<Duration>,
+            &'a CurrentTimeMilliseconds,
+        ),
+    ) -> Result<Self, Self::Error> {
+        Self::try_new(on, None, None, minimum_on, minimum_off, now)
+    }
+}
+
 impl BangBang for TimeConstrainedOnOff<'_> {
+    #[inline]
     fn state(&self) -> BangBangState {
         self.bang_bang.state()
     }
 
+    // a veto and a time constraint can both surface as the same upstream error here, callers
+    // that need to tell them apart use `try_set`
+    #[inline]
     fn set(&mut self, new_state: BangBangState) -> Result<(), BangBangError> {
-        let current_state = self.state();
-        let time_delta = assess_time_delta(self.last_changed, (self.now)());
+        self.try_set(new_state).map_err(BangBangError::from)
+    }
+}
 
-        let min_duration = match current_state {
-            BangBangState::A => self.minimum_off,
-            BangBangState::B => self.minimum_on,
-        };
-        if let Some(min_duration) = min_duration {
-            if min_duration > Duration::from_millis(u64::from(time_delta)) {
-                return Err(BangBangError::StateChangeTemporarilyConstrained {
-                    from: current_state,
-                    to: new_state,
-                    code: 0,
-                });
-            }
-        };
+/// hypothetical outcome of a state transition as reported by [`TimeConstrainedOnOff::simulate_bang`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimResult {
+    /// the time constraints are met and the handler for the contained state would be called
+    WouldTransition(BangBangState),
+    /// the minimum time constraint of the current state would block the transition
+    BlockedByConstraint {
+        /// time left until the constraint is satisfied
+        remaining: Duration,
+    },
+    /// the time constraints are met but the veto predicate expects the handler for the contained
+    /// state to veto the transition
+    LikelyVetoed(BangBangState),
+}
 
-        self.bang_bang.set(new_state)?;
-        self.last_changed = (self.now)();
+/// what happened during a call to [`TimeConstrainedOnOff::step`]
+#[derive(Debug, Clone, Copy)]
+pub enum StepReport {
+    /// no maximum was reached and no command was given
+    NoCommand,
+    /// the command was carried out and the controller is now in the contained state
+    Transitioned(BangBangState),
+    /// the minimum time constraint of the current state blocked the command
+    Blocked {
+        /// time left until the constraint is satisfied
+        remaining: Duration,
+    },
+    /// the maximum time of the previous state was reached and the controller was forced into
+    /// the contained state, any command was discarded
+    Forced(BangBangState),
+    /// the command or forced transition failed for a reason other than a minimum time
+    /// constraint, such as a handler veto
+    Failed(TransitionError),
+}
+
+/// request to change the state of a controller, see [`TimeConstrainedOnOff::apply`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// transition to the `on` state
+    On,
+    /// transition to the `off` state
+    Off,
+    /// transition to whichever state the controller is not currently in
+    Toggle,
+}
+
+/// state of a controller that may not yet be known, see [`TimeConstrainedOnOff::tristate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tristate {
+    /// the controller is `off`
+    Off,
+    /// the controller is `on`
+    On,
+    /// the controller has not been set since it was created with
+    /// [`TimeConstrainedOnOff::new_unknown`]
+    Unknown,
+}
+
+/// time constraints and initial state of a controller without any of its closures, see
+/// [`TimeConstrainedOnOff::from_config`] and [`TimeConstrainedOnOff::config`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Config {
+    /// whether the controller starts in the `on` state
+    pub on: bool,
+    /// minimum time the controller must remain `on` before it may transition to `off`
+    pub minimum_on: Option<Duration>,
+    /// minimum time the controller must remain `off` before it may transition to `on`
+    pub minimum_off: Option<Duration>,
+    /// maximum time the controller may remain `on` before it is forced `off`
+    pub maximum_on: Option<Duration>,
+    /// maximum time the controller may remain `off` before it is forced `on`
+    pub maximum_off: Option<Duration>,
+}
+
+/// renders one `key = value` line per setting, with durations in milliseconds, for writing to a
+/// TOML style config file
+///
+/// Constraints that are not set are left out, for example `on = true` and `min_on_ms = 500` for a
+/// controller with only a minimum `on` time.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "on = {}", self.on)?;
+
+        let constraints = [
+            ("min_on_ms", self.minimum_on),
+            ("min_off_ms", self.minimum_off),
+            ("max_on_ms", self.maximum_on),
+            ("max_off_ms", self.maximum_off),
+        ];
+        for &(key, constraint) in &constraints {
+            if let Some(constraint) = constraint {
+                writeln!(f, "{} = {}", key, constraint.as_millis())?;
+            }
+        }
 
         Ok(())
     }
 }
 
+/// outcome chosen by a fallback installed with [`TimeConstrainedOnOff::with_fallback`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// keep the transition blocked so it can be retried later, same as having no fallback
+    Wait,
+    /// ignore the time constraint and proceed with the transition, calling the handler as usual
+    Force,
+    /// abandon the transition, reported as [`TransitionError::Aborted`] rather than
+    /// [`TransitionError::Constrained`]
+    Abort,
+}
+
+/// how a clock reading that is less than the previous reading is interpreted, see
+/// [`TimeConstrainedOnOff::with_clock_policy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClockPolicy {
+    /// the counter restarted from zero, so the time passed is only the current reading (default)
+    #[default]
+    Restarting,
+    /// the counter wrapped around past `u32::MAX`, so the time passed is the wrapped difference
+    Wrapping,
+    /// the counter stopped at its maximum, so no time is considered to have passed
+    Saturating,
+    /// the counter should never go backwards, so this is logged as a clock error and no time is
+    /// considered to have passed, conservatively blocking transitions
+    Monotonic,
+}
+
+/// which transitions restart the timer that time constraints are measured from, see
+/// [`TimeConstrainedOnOff::with_timer_reset_policy`]
+///
+/// Transitions that do not restart the timer leave the new state measured from the last one
+/// that did, so its minimum may already be satisfied and its maximum may already be near.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimerResetPolicy {
+    /// every transition restarts the timer (default)
+    #[default]
+    Always,
+    /// only transitions from `on` to `off` restart the timer
+    OnToOff,
+    /// only transitions from `off` to `on` restart the timer
+    OffToOn,
+    /// the timer is only started when the controller is created
+    Never,
+}
+
+impl TimerResetPolicy {
+    fn resets(self, from: BangBangState, to: BangBangState) -> bool {
+        match self {
+            TimerResetPolicy::Always => true,
+            TimerResetPolicy::OnToOff => from == BangBangState::B && to == BangBangState::A,
+            TimerResetPolicy::OffToOn => from == BangBangState::A && to == BangBangState::B,
+            TimerResetPolicy::Never => false,
+        }
+    }
+}
+
+/// whether a minimum time constraint is satisfied at the exact moment it elapses, see
+/// [`TimeConstrainedOnOff::with_boundary_policy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryPolicy {
+    /// a transition is permitted once the time in the state equals the minimum (default)
+    #[default]
+    Inclusive,
+    /// a transition is only permitted once the time in the state exceeds the minimum, blocking
+    /// for one more millisecond at the boundary
+    Exclusive,
+}
+
 impl<'a> TimeConstrainedOnOff<'a> {
+    /// unit the `now` method must report time in, which is [`TIME_UNIT`]
+    pub fn time_unit() -> &'static str {
+        TIME_UNIT
+    }
+
     /// creates a new on/off controller with optional notification handlers for each state transition
+    ///
+    /// Time constraints longer than the `u32` millisecond clock can measure, roughly 49.7 days,
+    /// are clamped to `u32::MAX` milliseconds with a logged warning, here and in every setter.
     pub fn new(
         on: bool,
         handle_on: Option<&'a mut StateChangeHander>,
@@ -188,14 +580,318 @@ impl<'a> TimeConstrainedOnOff<'a> {
         minimum_off: Option<Duration>,
         now: &'a CurrentTimeMilliseconds,
     ) -> Self {
-        let last_changed = now();
+        Self::from_parts(
+            OnOff::new(on, None, None),
+            handle_on,
+            handle_off,
+            minimum_on,
+            minimum_off,
+            now,
+            Some(now()),
+        )
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] without handlers from
+    /// the time constraints and initial state in `config`
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if a maximum is shorter than the minimum of the same state.
+    pub fn from_config(config: Config, now: &'a CurrentTimeMilliseconds) -> Self {
+        let mut on_off = Self::new(
+            config.on,
+            None,
+            None,
+            config.minimum_on,
+            config.minimum_off,
+            now,
+        );
+        on_off.set_maximum_on(config.maximum_on);
+        on_off.set_maximum_off(config.maximum_off);
+        on_off
+    }
+
+    /// time constraints of the controller and its current state as the initial state, for
+    /// creating a similarly configured controller with [`TimeConstrainedOnOff::from_config`]
+    pub fn config(&self) -> Config {
+        Config {
+            on: self.is_on(),
+            minimum_on: self.minimum_on,
+            minimum_off: self.minimum_off,
+            maximum_on: self.maximum_on,
+            maximum_off: self.maximum_off,
+        }
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new_deferred`] whose state is
+    /// unknown until first set, for equipment whose state cannot be read back at power up
+    ///
+    /// While unknown, [`TimeConstrainedOnOff::is_on`] and [`TimeConstrainedOnOff::is_off`] both
+    /// return `false`, [`TimeConstrainedOnOff::tristate`] reports [`Tristate::Unknown`] and
+    /// [`BangBang::state`] reports `off` as a placeholder. The first transition to either state
+    /// calls its handler and is not subject to a minimum, as there has been no time in a state.
+    pub fn new_unknown(
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        let mut on_off =
+            Self::new_deferred(false, handle_on, handle_off, minimum_on, minimum_off, now);
+        on_off.known = false;
+        on_off
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] without calling the
+    /// `now` method, for clocks that are not ready yet
+    ///
+    /// The timer starts with [`TimeConstrainedOnOff::start`] or the first transition, whichever
+    /// comes first. Until then no time has been spent in the initial state, so its minimum does
+    /// not block that first transition and its maximum is not enforced.
+    pub fn new_deferred(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        Self::from_parts(
+            OnOff::new(on, None, None),
+            handle_on,
+            handle_off,
+            minimum_on,
+            minimum_off,
+            now,
+            None,
+        )
+    }
+
+    /// starts the timer of a controller created with [`TimeConstrainedOnOff::new_deferred`] as of
+    /// the provided time, which restarts it for any other controller
+    pub fn start(&mut self, now: u32) {
+        self.last_changed = now;
+        self.entered_at = now;
+        self.active_time = 0;
+        self.primed = 0;
+        self.started = true;
+        self.last_reading = Some(now);
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] with minimum time
+    /// constraints given in milliseconds, the same unit as the `now` method
+    ///
+    /// This is a convenience for callers that already hold millisecond counts. The counts are
+    /// converted to [`Duration`]s and stored and checked exactly as if they had been passed to
+    /// [`TimeConstrainedOnOff::new`], which loses nothing as every `u32` count converts exactly.
+    pub fn new_ms(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on_ms: Option<u32>,
+        minimum_off_ms: Option<u32>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        Self::new(
+            on,
+            handle_on,
+            handle_off,
+            minimum_on_ms.map(|ms| Duration::from_millis(u64::from(ms))),
+            minimum_off_ms.map(|ms| Duration::from_millis(u64::from(ms))),
+            now,
+        )
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`], returning an error
+    /// instead if [`TimeConstrainedOnOff::validate`] finds the configuration infeasible or a
+    /// minimum would have to be clamped to the range of the clock
+    pub fn try_new(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Result<Self, ConfigError> {
+        if exceeds_clock_range(minimum_on) {
+            return Err(ConfigError::ExceedsClockRange(BangBangState::B));
+        }
+        if exceeds_clock_range(minimum_off) {
+            return Err(ConfigError::ExceedsClockRange(BangBangState::A));
+        }
+
+        let on_off = Self::new(on, handle_on, handle_off, minimum_on, minimum_off, now);
+        on_off.validate()?;
+        Ok(on_off)
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] with the same minimum
+    /// time constraint for both states
+    pub fn with_symmetric_minimum(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum: Duration,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        Self::new(on, handle_on, handle_off, Some(minimum), Some(minimum), now)
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] with a single handler
+    /// that is called for transitions in both directions with the state being transitioned to
+    pub fn with_unified_handler(
+        on: bool,
+        handler: &'a mut UnifiedStateChangeHandler,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        let mut on_off = Self::new(on, None, None, minimum_on, minimum_off, now);
+        on_off.unified_handler = Some(handler);
+        on_off
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] without any handlers
+    pub fn without_handlers(
+        on: bool,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        Self::new(on, None, None, minimum_on, minimum_off, now)
+    }
+
+    /// creates a new on/off controller like [`TimeConstrainedOnOff::new`] that measures time with
+    /// [`std_clock::now_ms`]
+    #[cfg(feature = "std")]
+    pub fn new_std(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+    ) -> Self {
+        Self::new(
+            on,
+            handle_on,
+            handle_off,
+            minimum_on,
+            minimum_off,
+            &std_clock::now_ms,
+        )
+    }
+
+    /// wraps an existing on/off controller with time constraints, preserving its current state
+    /// and the handlers it was created with
+    ///
+    /// The minimum time constraint of the current state is measured from the moment of wrapping.
+    /// The handlers of the wrapped controller remain its own and are called by it on every state
+    /// change, including those made by [`TimeConstrainedOnOff::reset_to`] without firing handlers.
+    pub fn from_onoff(
+        onoff: OnOff<'a>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        let mut on_off =
+            Self::from_parts(onoff, None, None, minimum_on, minimum_off, now, Some(now()));
+        on_off.wraps_onoff = true;
+
+        on_off
+    }
+
+    /// strips the time constraints, returning an on/off controller in the current state that
+    /// transitions freely, the inverse of [`TimeConstrainedOnOff::from_onoff`]
+    ///
+    /// The `on` and `off` handlers of this controller are handed to the returned one, so passing
+    /// it back to [`TimeConstrainedOnOff::from_onoff`] restores the constraints with the same
+    /// handler wiring. A controller wrapped with [`TimeConstrainedOnOff::from_onoff`] is returned
+    /// as it was, with its own handlers, so any handlers later installed here are dropped, as is
+    /// a unified handler, which cannot be carried over.
+    pub fn into_unconstrained(self) -> OnOff<'a> {
+        if self.wraps_onoff {
+            return self.bang_bang;
+        }
+
+        OnOff::new(self.bang_bang.is_on(), self.handle_on, self.handle_off)
+    }
+
+    fn from_parts(
+        bang_bang: OnOff<'a>,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        now: &'a CurrentTimeMilliseconds,
+        started_at: Option<u32>,
+    ) -> Self {
+        let minimum_on = clamp_to_clock("minimum_on", minimum_on);
+        let minimum_off = clamp_to_clock("minimum_off", minimum_off);
+
+        let last_changed = started_at.unwrap_or(0);
 
         let on_off = Self {
-            bang_bang: OnOff::new(on, handle_on, handle_off),
+            bang_bang,
+            handle_on,
+            handle_off,
+            unified_handler: None,
             minimum_on,
             minimum_off,
+            maximum_on: None,
+            maximum_off: None,
             last_changed,
+            primed: 0,
             now,
+            off_clock: None,
+            fallback: None,
+            clock_policy: ClockPolicy::default(),
+            history: History::new(),
+            last_blocked_at: None,
+            was_ready: false,
+            constraints_suspended: false,
+            proportional_off: None,
+            dynamic_minimum_off: None,
+            #[cfg(feature = "log")]
+            log_timing: true,
+            stall_threshold: None,
+            identical_blocked_readings: 0,
+            blocked_attempts: 0,
+            reader: None,
+            handler_timing: false,
+            last_on_handler_duration: None,
+            last_off_handler_duration: None,
+            last_error: None,
+            tolerance: Duration::from_millis(0),
+            handler_is_advisory: false,
+            on_ready: None,
+            gated: false,
+            active_time: 0,
+            veto_predicate: None,
+            time_on: 0,
+            time_off: 0,
+            checkpoint: (0, 0),
+            restored_transitions: (0, 0),
+            backward_jumps: 0,
+            last_reading: started_at,
+            backward_jump_threshold: None,
+            timer_reset_policy: TimerResetPolicy::default(),
+            entered_at: last_changed,
+            started: started_at.is_some(),
+            timing_hysteresis: Duration::from_millis(0),
+            boundary_raised: false,
+            settling: Duration::from_millis(0),
+            minimum_condition: None,
+            known: true,
+            unscaled_minimums: None,
+            transitions: 0,
+            boundary_policy: BoundaryPolicy::default(),
+            pending: None,
+            last_on_at: None,
+            last_off_at: None,
+            minimum_bypassed: false,
+            previous_changed: None,
+            wraps_onoff: false,
         };
 
         #[cfg(feature = "log")]
@@ -204,38 +900,1874 @@ impl<'a> TimeConstrainedOnOff<'a> {
         on_off
     }
 
-    /// convienence method for checking if the controller is in the `on` state
-    pub fn is_on(&self) -> bool {
-        self.bang_bang.is_on()
+    /// adds `delta` to the active time spent in the current state, which is what the time
+    /// constraints are measured against when created with
+    /// [`TimeConstrainedOnOff::with_gated_time`]
+    ///
+    /// Active time saturates at `u32::MAX` milliseconds and restarts from zero at each transition.
+    /// Without gated time this has no effect.
+    pub fn add_active_time(&mut self, delta: Duration) {
+        self.active_time = self.active_time.saturating_add(saturating_millis(delta));
     }
 
-    /// convienence method for checking if the controller is in the `off` state
-    pub fn is_off(&self) -> bool {
-        self.bang_bang.is_off()
+    /// treats the current state as having already been active for `elapsed`, so that its minimum
+    /// time constraint is satisfied that much sooner
+    ///
+    /// Useful at startup when the physical state predates the controller and the first transition
+    /// should not be delayed. The credit saturates at `u32::MAX` milliseconds and is discarded at
+    /// the next transition.
+    pub fn prime(&mut self, elapsed: Duration) {
+        self.primed = self.primed.saturating_add(saturating_millis(elapsed));
     }
-}
 
-fn assess_time_delta(prior_milliseconds: u32, later_milliseconds: u32) -> u32 {
-    // if we have overflown our u32 ms counter or otherwise have less millisecond counted
-    // now than previously, assume that the delta can be only as large as the current value
-    if later_milliseconds < prior_milliseconds {
+    /// installs a fallback that decides what happens when a time constraint blocks a transition
+    ///
+    /// The fallback only runs when a minimum time constraint blocks the transition; it is not
+    /// consulted when a state change handler vetoes the transition, and it is never called by
+    /// [`TimeConstrainedOnOff::simulate_bang`].
+    pub fn with_fallback(mut self, fallback: &'a mut ConstraintFallback) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// changes how a clock reading that is less than the previous reading is interpreted
+    pub fn with_clock_policy(mut self, clock_policy: ClockPolicy) -> Self {
+        self.clock_policy = clock_policy;
+        self
+    }
+
+    /// measures time in the `on` state with `on_clock` and time in the `off` state with
+    /// `off_clock`, replacing the `now` method
+    ///
+    /// Each constraint is checked against the clock of the state being left, and the state being
+    /// entered is timestamped with its own clock. Times passed to the `_at` methods are taken to
+    /// be readings of the clock of the current state. Transition history records each transition
+    /// on the clock of the state entered, so [`TimeConstrainedOnOff::transitions_within`] is only
+    /// meaningful with a single clock.
+    pub fn with_dual_clock(
+        mut self,
+        on_clock: &'a CurrentTimeMilliseconds,
+        off_clock: &'a CurrentTimeMilliseconds,
+    ) -> Self {
+        self.now = on_clock;
+        self.off_clock = Some(off_clock);
+        if self.started {
+            self.last_changed = self.read_clock(self.state());
+            self.entered_at = self.last_changed;
+        }
+        self
+    }
+
+    /// reports the clock as stalled once `threshold_calls` consecutive blocked transitions have
+    /// all read the same time, see [`TimeConstrainedOnOff::clock_stalled`]
+    ///
+    /// A clock that never advances leaves any nonzero minimum unsatisfiable forever, so this
+    /// helps diagnose a dead timer. A warning is logged when the threshold is reached.
+    pub fn with_stall_detection(mut self, threshold_calls: u32) -> Self {
+        self.stall_threshold = Some(threshold_calls);
+        self
+    }
+
+    /// limits which transitions restart the timer that minimum and maximum time constraints are
+    /// measured from, see [`TimerResetPolicy`]
+    pub fn with_timer_reset_policy(mut self, timer_reset_policy: TimerResetPolicy) -> Self {
+        self.timer_reset_policy = timer_reset_policy;
+        self
+    }
+
+    /// chooses whether a transition is permitted when the time in the state exactly equals its
+    /// minimum, see [`BoundaryPolicy`]
+    ///
+    /// Useful to match the semantics of another system when the clocks involved tick at different
+    /// resolutions. Tolerance and timing hysteresis are applied before the boundary is compared.
+    pub fn with_boundary_policy(mut self, boundary_policy: BoundaryPolicy) -> Self {
+        self.boundary_policy = boundary_policy;
+        self
+    }
+
+    /// logs negative time deltas at error level instead of warning level once the clock has been
+    /// seen running backwards `threshold` times, see [`TimeConstrainedOnOff::backward_jump_count`]
+    ///
+    /// A single backward jump is a counter overrun, repeated ones point at a faulty time source.
+    pub fn with_backward_jump_threshold(mut self, threshold: u32) -> Self {
+        self.backward_jump_threshold = Some(threshold);
+        self
+    }
+
+    /// publishes the state of the controller to `mirror` after every transition, so that it can be
+    /// read from elsewhere through [`TimeConstrainedOnOff::split`]
+    pub fn with_state_mirror(mut self, mirror: &'a AtomicBool) -> Self {
+        let reader = StateReader::new(mirror);
+        reader.publish(self.is_on());
+        self.reader = Some(reader);
+        self
+    }
+
+    /// measures how long each handler takes to run by reading the clock before and after it, see
+    /// [`TimeConstrainedOnOff::last_on_handler_duration`]
+    ///
+    /// This costs two extra clock reads per handler call. Transitions are timestamped before the
+    /// handler runs, so time spent in a slow handler counts towards the minimum of the new state.
+    pub fn with_handler_timing(mut self) -> Self {
+        self.handler_timing = true;
+        self
+    }
+
+    /// permits transitions up to `tolerance` before the minimum time constraint is satisfied, to
+    /// absorb jitter in the clock (zero by default)
+    ///
+    /// The tolerance is also deducted from the time reported as remaining.
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// only enforces the minimum time constraints while `condition` returns `true`, which is
+    /// evaluated each time the remaining time is needed, such as by every transition
+    ///
+    /// For loads where rapid cycling only does harm under some conditions, for example a
+    /// compressor that may cycle freely while cold. Settling and maximums are unaffected.
+    pub fn conditional_minimum(mut self, condition: &'a MinimumCondition) -> Self {
+        self.minimum_condition = Some(condition);
+        self
+    }
+
+    /// blocks transitions until `settling` has passed since the last transition, in addition to
+    /// the minimum time constraints, see [`TimeConstrainedOnOff::is_settled`]
+    ///
+    /// For equipment such as valves that take time to physically reach the commanded state.
+    /// Settling is always measured in real time since the last transition, regardless of gated
+    /// time or the timer reset policy.
+    pub fn with_settling(mut self, settling: Duration) -> Self {
+        self.settling = settling;
+        self
+    }
+
+    /// once a transition has been blocked, requires `hysteresis` beyond the minimum time
+    /// constraint before permitting one, until the next transition lowers it again (zero by
+    /// default)
+    ///
+    /// Keeps bursts of commands arriving at a jittery clock reading right at the boundary from
+    /// being alternately blocked and permitted.
+    pub fn with_timing_hysteresis(mut self, hysteresis: Duration) -> Self {
+        self.timing_hysteresis = hysteresis;
+        self
+    }
+
+    /// lets transitions proceed when a handler returns an error, which is logged as a warning and
+    /// recorded as a [`TransitionError::Vetoed`] in [`TimeConstrainedOnOff::last_error`]
+    ///
+    /// For handlers whose side effect is not essential to the transition, where the state must
+    /// change regardless, for example for safety.
+    pub fn with_advisory_handlers(mut self) -> Self {
+        self.handler_is_advisory = true;
+        self
+    }
+
+    /// installs a callback that is called with the state that has become available to transition
+    /// to, once, when the minimum time constraint of the current state is first seen satisfied
+    ///
+    /// There is no background evaluation: the boundary is only noticed by
+    /// [`TimeConstrainedOnOff::set_at`] and its variants, [`TimeConstrainedOnOff::poll_at`] and
+    /// its variants, and [`TimeConstrainedOnOff::poll_ready`], so one of these must be called
+    /// regularly. The callback shares its edge with `poll_ready`, so the two never both report the
+    /// same boundary.
+    pub fn with_on_ready(mut self, callback: &'a mut ReadyCallback) -> Self {
+        self.on_ready = Some(callback);
+        self
+    }
+
+    /// measures time in each state only as reported through
+    /// [`TimeConstrainedOnOff::add_active_time`] instead of by the clock
+    ///
+    /// For duty-gated systems where constraints should only count down while the system is
+    /// active, such as a cooldown that freezes during a power-save pause. This applies to both
+    /// the minimum and maximum time constraints, the clock is still used for timestamps.
+    pub fn with_gated_time(mut self) -> Self {
+        self.gated = true;
+        self
+    }
+
+    /// extends the minimum time in the `off` state to `factor` times the duration of the `on`
+    /// period that preceded it, whenever that is longer than the configured minimum
+    ///
+    /// This is the anti-short-cycle cooldown used by compressors and similar loads, where a long
+    /// run needs a correspondingly long rest. The extension only applies to the `off` period
+    /// immediately following each `on` period.
+    pub fn with_proportional_off(mut self, factor: f32) -> Self {
+        self.proportional_off = Some(factor);
+        self
+    }
+
+    /// requires every `off` period to last at least `ratio` times the `on` period before it,
+    /// combined with the configured `minimum_off` by taking the longer of the two
+    ///
+    /// An alias of [`TimeConstrainedOnOff::with_proportional_off`], named for configurations that
+    /// think of the off time relationally, such as at least half the preceding on time.
+    pub fn relative_minimum_off(self, ratio: f32) -> Self {
+        self.with_proportional_off(ratio)
+    }
+
+    /// same as [`BangBang::set`] but reports why the transition did not take place as a
+    /// [`TransitionError`], including the time remaining when blocked by a time constraint
+    ///
+    /// The clock is read once, before any handler is called, and that reading is used both to
+    /// check the time constraint and to timestamp the transition.
+    #[inline]
+    pub fn try_set(&mut self, new_state: BangBangState) -> Result<(), TransitionError> {
+        self.set_at(self.read_clock(self.state()), new_state)
+    }
+
+    /// same as [`BangBang::bang`] but reports why the transition did not take place as a
+    /// [`TransitionError`], including the time remaining when blocked by a time constraint
+    #[inline]
+    pub fn try_bang(&mut self) -> Result<(), TransitionError> {
+        let new_state = opposite(self.state());
+        self.try_set(new_state)
+    }
+
+    /// same as [`TimeConstrainedOnOff::try_bang`], returning how long the controller spent in the
+    /// state it just left
+    ///
+    /// The dwell is measured from when the state was actually entered, so it is not shortened by
+    /// a [`TimerResetPolicy`] that keeps the timer running across transitions.
+    pub fn bang_timed(&mut self) -> Result<Duration, TransitionError> {
+        let now = self.read_clock(self.state());
+        let dwell = self.time_since_entered(now);
+        self.bang_at(now)?;
+
+        Ok(Duration::from_millis(dwell))
+    }
+
+    /// advances a settable clock by the time remaining before a transition is permitted, then
+    /// transitions to the opposite state as [`TimeConstrainedOnOff::try_bang`] does
+    ///
+    /// `clock` is called once with the milliseconds to advance by, rounded up, and must move the
+    /// clock behind the `now` method forward by that much. Intended for tests and simulations.
+    pub fn fast_forward_and_bang(
+        &mut self,
+        clock: &mut impl FnMut(u32),
+    ) -> Result<(), TransitionError> {
+        let remaining = self.time_remaining().unwrap_or_default();
+        // constraints less the tolerance may leave a fraction of a millisecond
+        clock(saturating_millis(remaining + Duration::from_nanos(999_999)));
+
+        self.try_bang()
+    }
+
+    /// calls [`BangBang::bang`] `count` times, returning how many of the calls transitioned
+    ///
+    /// Blocked or vetoed calls do not stop the batch, the remaining calls are still attempted.
+    pub fn bang_n(&mut self, count: usize) -> usize {
+        (0..count).filter(|_| self.bang().is_ok()).count()
+    }
+
+    /// same as [`TimeConstrainedOnOff::try_set`] but evaluated against the provided time instead
+    /// of the `now` method
+    ///
+    /// Requesting the state the controller is already in succeeds without calling a handler,
+    /// recording a transition or restarting the timer.
+    #[inline]
+    pub fn set_at(&mut self, now: u32, new_state: BangBangState) -> Result<(), TransitionError> {
+        if self.known && new_state == self.state() {
+            return Ok(());
+        }
+
+        // cleared first, as an advisory handler may record an error on a successful transition
+        self.last_error = None;
+        let result = self.transition_at(now, new_state);
+        if let Err(error) = result {
+            self.last_error = Some(error);
+        }
+        result
+    }
+
+    #[inline]
+    fn transition_at(&mut self, now: u32, new_state: BangBangState) -> Result<(), TransitionError> {
+        self.observe_clock(now);
+        if self.on_ready.is_some() {
+            let _ = self.observe_ready(now);
+        }
+
+        let from = self.state();
+        let forced = self.check_constraint(now, new_state)?;
+        let unmet = if forced {
+            None
+        } else {
+            self.unmet_minimum(now)
+        };
+
+        #[cfg(feature = "debug-strict")]
+        debug_assert!(
+            unmet.is_none(),
+            "transition from {:?} to {:?} at {}ms bypassed an active minimum of {:?}",
+            from,
+            new_state,
+            now,
+            unmet.unwrap_or_default()
+        );
+
+        self.change_state(new_state)
+            .map_err(TransitionError::Vetoed)?;
+        self.mark_changed(from, now);
+        self.minimum_bypassed = unmet.is_some();
+
+        Ok(())
+    }
+
+    /// whether `upstream` permits this controller to turn `on`, which is once `upstream` is `on`
+    /// and its minimum `on` time has been satisfied
+    ///
+    /// Models staged equipment, such as a second heating stage that may only start once the
+    /// first has been running for its minimum.
+    #[must_use]
+    pub fn link_enable(&self, upstream: &TimeConstrainedOnOff<'_>) -> bool {
+        upstream.is_on() && upstream.time_remaining().is_none()
+    }
+
+    /// same as [`TimeConstrainedOnOff::try_set`] but refuses to turn `on` unless
+    /// [`TimeConstrainedOnOff::link_enable`] permits it, turning `off` is never held back by
+    /// `upstream`
+    pub fn set_linked(
+        &mut self,
+        upstream: &TimeConstrainedOnOff<'_>,
+        new_state: BangBangState,
+    ) -> Result<(), TransitionError> {
+        if new_state == BangBangState::B && self.is_off() && !self.link_enable(upstream) {
+            let error = TransitionError::UpstreamNotReady {
+                from: self.state(),
+                to: new_state,
+            };
+            self.last_error = Some(error);
+            return Err(error);
+        }
+
+        self.try_set(new_state)
+    }
+
+    /// same as [`TimeConstrainedOnOff::try_bang`] but evaluated against the provided time instead
+    /// of the `now` method
+    #[inline]
+    pub fn bang_at(&mut self, now: u32) -> Result<(), TransitionError> {
+        let new_state = opposite(self.state());
+        self.set_at(now, new_state)
+    }
+
+    /// puts the controller into `state` regardless of time constraints, restarting the minimum
+    /// time constraint of that state
+    ///
+    /// When `fire_handler` is `true` the handler for `state` is called first and may veto the
+    /// reset, leaving the controller unchanged; when `false` no handler is called.
+    pub fn reset_to(
+        &mut self,
+        state: BangBangState,
+        fire_handler: bool,
+    ) -> Result<(), BangBangError> {
         #[cfg(feature = "log")]
-        warn!(
-            "time delta from {}ms to {}ms is negative, assuming counter overrun, delta is {}ms",
-            prior_milliseconds, later_milliseconds, later_milliseconds
+        debug!(
+            "resetting {:?} to {:?}, fire handler is {}",
+            self, state, fire_handler
         );
-        return later_milliseconds;
-    };
 
-    let time_delta = later_milliseconds - prior_milliseconds;
+        let from = self.state();
+        let now = self.read_clock(from);
+        if fire_handler {
+            self.change_state(state)?;
+        } else {
+            self.bang_bang.set(state)?;
+        }
+        self.mark_changed(from, now);
 
-    #[cfg(feature = "log")]
-    trace!(
-        "time delta from {}ms to {}ms is {}ms",
-        prior_milliseconds,
-        later_milliseconds,
-        time_delta,
-    );
-
-    time_delta
+        Ok(())
+    }
+
+    /// flips to the opposite state regardless of time constraints, calling the handler for that
+    /// state, which may veto the transition and leave the controller unchanged
+    ///
+    /// This is the emergency counterpart of [`BangBang::bang`], the same as calling
+    /// [`TimeConstrainedOnOff::reset_to`] with the opposite state and `fire_handler` set.
+    pub fn force_toggle(&mut self) -> Result<(), BangBangError> {
+        let new_state = opposite(self.state());
+        self.reset_to(new_state, true)
+    }
+
+    /// carries out a [`Command`] subject to the same constraints as [`BangBang::set`]
+    pub fn apply(&mut self, command: Command) -> Result<(), BangBangError> {
+        match command {
+            Command::On => self.set(BangBangState::B),
+            Command::Off => self.set(BangBangState::A),
+            Command::Toggle => self.bang(),
+        }
+    }
+
+    /// advances the controller to the provided time, carrying out `command` if given, and reports
+    /// what happened
+    ///
+    /// The maximum time of the current state is evaluated first, exactly as
+    /// [`TimeConstrainedOnOff::poll_at`] would. When it forces a transition, or the forced
+    /// transition fails, the command is discarded for this step so the caller can decide whether
+    /// to resubmit it. Otherwise the command is evaluated against the minimum time constraints as
+    /// [`TimeConstrainedOnOff::set_at`] would.
+    pub fn step(&mut self, now: u32, command: Option<Command>) -> StepReport {
+        match self.force_at(now) {
+            Ok(Some(forced)) => return StepReport::Forced(forced),
+            Ok(None) => {}
+            Err(error) => return StepReport::Failed(error),
+        }
+
+        let new_state = match command {
+            Some(Command::On) => BangBangState::B,
+            Some(Command::Off) => BangBangState::A,
+            Some(Command::Toggle) => opposite(self.state()),
+            None => return StepReport::NoCommand,
+        };
+
+        match self.set_at(now, new_state) {
+            Ok(()) => StepReport::Transitioned(new_state),
+            Err(TransitionError::Constrained { remaining, .. }) => {
+                StepReport::Blocked { remaining }
+            }
+            Err(error) => StepReport::Failed(error),
+        }
+    }
+
+    /// submits `command` to be carried out by the next [`TimeConstrainedOnOff::tick`], replacing
+    /// any command still pending
+    pub fn request(&mut self, command: Command) {
+        self.pending = Some(command);
+    }
+
+    /// command submitted with [`TimeConstrainedOnOff::request`] that has yet to be carried out
+    pub fn pending_command(&self) -> Option<Command> {
+        self.pending
+    }
+
+    /// advances the controller to the provided time as [`TimeConstrainedOnOff::step`] does with
+    /// the pending command, for fixed-rate loops that accept commands between ticks
+    ///
+    /// A command blocked by a minimum time constraint, or not evaluated because a maximum forced
+    /// a transition, stays pending and is retried at the next tick. Any other outcome consumes it.
+    pub fn tick(&mut self, now: u32) -> StepReport {
+        let report = self.step(now, self.pending);
+        if !matches!(report, StepReport::Blocked { .. } | StepReport::Forced(_)) {
+            self.pending = None;
+        }
+
+        report
+    }
+
+    /// drives the controller from `source` for `steps` steps, reading the time of each step from
+    /// `clock`, yielding the time and [`TimeConstrainedOnOff::step`] report of each
+    ///
+    /// `clock` is independent of the `now` method, so a simulation can advance time as quickly as
+    /// it likes. Commands for the state the controller is already in are not carried out, so they
+    /// do not restart its timer. Nothing happens unless the returned iterator is consumed.
+    pub fn run<'r, S: CommandSource, C: FnMut() -> u32>(
+        &'r mut self,
+        source: S,
+        clock: C,
+        steps: usize,
+    ) -> source::Run<'r, 'a, S, C> {
+        source::Run::new(self, source, clock, steps)
+    }
+
+    /// forces a transition out of the current state once its maximum time has been reached,
+    /// returning the state transitioned to, if any
+    ///
+    /// Maximums take precedence over minimums, so the forced transition ignores the minimum time
+    /// constraints, but the handler is still called and may veto it. Nothing is forced unless
+    /// this method is called.
+    pub fn poll(&mut self) -> Result<Option<BangBangState>, BangBangError> {
+        self.poll_at(self.read_clock(self.state()))
+    }
+
+    /// same as [`TimeConstrainedOnOff::poll`] but evaluated against the provided time instead of
+    /// the `now` method
+    pub fn poll_at(&mut self, now: u32) -> Result<Option<BangBangState>, BangBangError> {
+        self.force_at(now).map_err(BangBangError::from)
+    }
+
+    /// carries out [`TimeConstrainedOnOff::poll_at`], reporting failures as the
+    /// [`TransitionError`] that caused them
+    fn force_at(&mut self, now: u32) -> Result<Option<BangBangState>, TransitionError> {
+        self.observe_clock(now);
+        if self.on_ready.is_some() {
+            let _ = self.observe_ready(now);
+        }
+
+        let from = self.state();
+        let maximum = match self.maximum_for(from) {
+            Some(maximum) => maximum,
+            None => return Ok(None),
+        };
+        if Duration::from_millis(u64::from(self.elapsed_in_state(now))) < maximum {
+            return Ok(None);
+        }
+
+        #[cfg(feature = "log")]
+        debug!(
+            "{:?} exceeded maximum of {:?} in {:?}, forcing transition",
+            self, maximum, from
+        );
+
+        let to = opposite(from);
+        self.change_state(to).map_err(TransitionError::Vetoed)?;
+        self.mark_changed(from, now);
+
+        Ok(Some(to))
+    }
+
+    /// consumes the controller and samples its state every `step`, `steps` times, as the maximum
+    /// time constraints force it back and forth
+    ///
+    /// Time is simulated starting from the last transition and the `now` method is not used, so
+    /// the waveform is reproducible. Handlers are called for each forced transition as they would
+    /// be by [`TimeConstrainedOnOff::poll_at`], and a vetoed transition shows up as the state
+    /// remaining unchanged.
+    pub fn waveform(
+        mut self,
+        step: Duration,
+        steps: usize,
+    ) -> impl Iterator<Item = (u32, BangBangState)> + 'a {
+        let step = saturating_millis(step);
+        let mut timestamp = self.last_changed;
+
+        (0..steps).map(move |_| {
+            let _ = self.poll_at(timestamp);
+            let sample = (timestamp, self.state());
+            timestamp = timestamp.wrapping_add(step);
+            sample
+        })
+    }
+
+    /// state the controller would be in at the provided time if no commands were given and the
+    /// maximum time constraints were enforced exactly when reached
+    ///
+    /// Minimum time constraints and handlers are not considered. Several transitions may be
+    /// projected when the maximums of both states are shorter than the time until `now`.
+    pub fn projected_state_at(&self, now: u32) -> BangBangState {
+        let mut state = self.state();
+        let mut elapsed = u128::from(self.elapsed_in_state(now));
+
+        loop {
+            let maximum = match self.maximum_for(state) {
+                Some(maximum) => maximum.as_millis(),
+                None => return state,
+            };
+            if elapsed < maximum {
+                return state;
+            }
+
+            elapsed -= maximum;
+            state = opposite(state);
+
+            // each full on/off cycle ends where it started, skip them so long projections are cheap
+            if let (Some(on), Some(off)) = (self.maximum_on, self.maximum_off) {
+                let period = on.as_millis() + off.as_millis();
+                if period == 0 {
+                    return state;
+                }
+                elapsed %= period;
+            }
+        }
+    }
+
+    /// reports what [`TimeConstrainedOnOff::bang_at`] would do at the provided time without
+    /// changing any state or calling any handler
+    ///
+    /// Handlers cannot be dry-run, so a [`SimResult::WouldTransition`] only means that the time
+    /// constraints are met; the handler may still veto the transition when it is actually attempted.
+    #[must_use]
+    pub fn simulate_bang(&self, now: u32) -> SimResult {
+        let new_state = opposite(self.state());
+
+        match self.remaining_at(now) {
+            Some(remaining) => SimResult::BlockedByConstraint { remaining },
+            None if self.likely_vetoed(new_state) => SimResult::LikelyVetoed(new_state),
+            None => SimResult::WouldTransition(new_state),
+        }
+    }
+
+    /// whether a transition to `new_state` would be permitted right now by the time constraints
+    /// and, if one is installed, the veto predicate, without changing any state or calling any
+    /// handler
+    #[must_use]
+    pub fn can_set(&self, new_state: BangBangState) -> bool {
+        self.time_remaining().is_none() && !self.likely_vetoed(new_state)
+    }
+
+    /// installs or, with `None`, removes a side-effect free predicate that returns `true` when the
+    /// handler is expected to veto a transition to the given state
+    ///
+    /// The predicate is only consulted by [`TimeConstrainedOnOff::can_set`] and
+    /// [`TimeConstrainedOnOff::simulate_bang`] and is advisory: it never blocks a transition, and
+    /// the handler may still decide differently when it is actually called.
+    pub fn set_veto_predicate(&mut self, predicate: Option<&'a VetoPredicate>) {
+        self.veto_predicate = predicate;
+    }
+
+    fn likely_vetoed(&self, new_state: BangBangState) -> bool {
+        match self.veto_predicate {
+            Some(predicate) => predicate(new_state),
+            None => false,
+        }
+    }
+
+    /// `on_value` when the controller is `on`, otherwise `off_value`
+    #[inline]
+    pub fn mapped<T>(&self, on_value: T, off_value: T) -> T
+    where
+        T: Copy,
+    {
+        if self.is_on() {
+            on_value
+        } else {
+            off_value
+        }
+    }
+
+    /// copyable read-only handle to the state of the controller, or `None` if no mirror was
+    /// installed with [`TimeConstrainedOnOff::with_state_mirror`]
+    ///
+    /// The handle borrows the mirror rather than the controller, so it can be handed to other
+    /// tasks while this controller continues to be changed.
+    pub fn split(&self) -> Option<StateReader<'a>> {
+        self.reader
+    }
+
+    /// how long the most recent call of the `on` handler took, if handler timing was enabled with
+    /// [`TimeConstrainedOnOff::with_handler_timing`] and the handler has been called
+    ///
+    /// Calls that vetoed the transition are measured too.
+    pub fn last_on_handler_duration(&self) -> Option<Duration> {
+        self.last_on_handler_duration
+    }
+
+    /// how long the most recent call of the `off` handler took, if handler timing was enabled
+    /// with [`TimeConstrainedOnOff::with_handler_timing`] and the handler has been called
+    ///
+    /// Calls that vetoed the transition are measured too.
+    pub fn last_off_handler_duration(&self) -> Option<Duration> {
+        self.last_off_handler_duration
+    }
+
+    /// checks that the configured constraints can be satisfied together, so that a controller
+    /// cannot silently lock up
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let limits = [
+            (BangBangState::B, self.minimum_on, self.maximum_on),
+            (BangBangState::A, self.minimum_off, self.maximum_off),
+        ];
+        for &(state, minimum, maximum) in &limits {
+            if let (Some(minimum), Some(maximum)) = (minimum, maximum) {
+                if minimum > maximum {
+                    return Err(ConfigError::MinimumExceedsMaximum(state));
+                }
+            }
+        }
+
+        match self.proportional_off {
+            Some(factor) if factor.is_nan() || factor < 0.0 => {
+                Err(ConfigError::InvalidProportionalFactor)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// verifies the internal bookkeeping of the controller is consistent, reporting the first
+    /// violation found
+    ///
+    /// Besides the bookkeeping, this checks that the most recent transition respected the minimum
+    /// of the state it left unless it was forced, that the timer never restarted earlier than it
+    /// last did, that the transition count agrees with the transitions recorded since it was
+    /// created or restored, and that the time accumulated in each state agrees with the retained
+    /// history.
+    ///
+    /// Intended for property based and fuzz testing, where it can be called after every
+    /// operation of a randomized sequence. It reads no clock and calls no handler.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        if let Some((_, state)) = self.history.iter().next() {
+            if state != self.state() {
+                return Err(InvariantViolation::HistoryDisagreesWithState);
+            }
+        }
+
+        if let Some(reader) = self.reader {
+            if reader.is_on() != self.is_on() {
+                return Err(InvariantViolation::MirrorDisagreesWithState);
+            }
+        }
+
+        let longest = Duration::from_millis(u64::from(u32::MAX));
+        let constraints = [
+            self.minimum_on,
+            self.minimum_off,
+            self.maximum_on,
+            self.maximum_off,
+        ];
+        if constraints
+            .iter()
+            .flatten()
+            .any(|&constraint| constraint > longest)
+        {
+            return Err(InvariantViolation::ConstraintOutOfRange);
+        }
+
+        if self.is_on() && self.dynamic_minimum_off.is_some() {
+            return Err(InvariantViolation::ExtendedMinimumWhileOn);
+        }
+
+        if self.identical_blocked_readings > 0 && self.last_blocked_at.is_none() {
+            return Err(InvariantViolation::StallCountWithoutBlock);
+        }
+
+        if self.minimum_bypassed {
+            return Err(InvariantViolation::MinimumBypassed);
+        }
+
+        if let Some(previous_changed) = self.previous_changed {
+            // as in `cmp_ready`, a step back of more than half the clock range is a wrap
+            if (self.last_changed.wrapping_sub(previous_changed) as i32) < 0 {
+                return Err(InvariantViolation::LastChangedRegressed);
+            }
+        }
+
+        let (restored, recorded_then) = self.restored_transitions;
+        let recorded_since = self.history.recorded().saturating_sub(recorded_then);
+        if self.transitions != restored.saturating_add(recorded_since) {
+            return Err(InvariantViolation::TransitionCountDisagreesWithHistory);
+        }
+
+        // with dual clocks the timestamps of consecutive transitions come from different clocks
+        if self.off_clock.is_none() {
+            let (mut on, mut off) = (0u64, 0u64);
+            let newer = self.history.iter();
+            for ((entered, state), (left, _)) in self.history.iter().skip(1).zip(newer) {
+                let dwell = u64::from(self.time_delta(entered, left));
+                match state {
+                    BangBangState::A => off += dwell,
+                    BangBangState::B => on += dwell,
+                }
+            }
+            if on > self.time_on || off > self.time_off {
+                return Err(InvariantViolation::TimeDisagreesWithHistory);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// why the most recent transition requested through [`BangBang::set`] or any of its variants
+    /// failed, or `None` if it succeeded or none has been requested
+    ///
+    /// With [`TimeConstrainedOnOff::with_advisory_handlers`] a transition can succeed and still
+    /// record the error returned by its handler.
+    pub fn last_error(&self) -> Option<&TransitionError> {
+        self.last_error.as_ref()
+    }
+
+    /// replaces or, with `None`, removes the handler for transitions to the `on` state, keeping
+    /// the state and timing of the controller
+    ///
+    /// A handler installed with [`TimeConstrainedOnOff::with_unified_handler`] takes precedence.
+    pub fn set_on_handler(&mut self, handler: Option<&'a mut StateChangeHander>) {
+        self.handle_on = handler;
+    }
+
+    /// replaces or, with `None`, removes the handler for transitions to the `off` state, keeping
+    /// the state and timing of the controller
+    ///
+    /// A handler installed with [`TimeConstrainedOnOff::with_unified_handler`] takes precedence.
+    pub fn set_off_handler(&mut self, handler: Option<&'a mut StateChangeHander>) {
+        self.handle_off = handler;
+    }
+
+    /// whether a handler was provided for transitions to the `on` state, including a unified one
+    ///
+    /// Handlers belonging to a controller wrapped with [`TimeConstrainedOnOff::from_onoff`] are
+    /// not visible to this controller and are not reported.
+    pub fn has_on_handler(&self) -> bool {
+        self.handle_on.is_some() || self.unified_handler.is_some()
+    }
+
+    /// whether a handler was provided for transitions to the `off` state, including a unified one
+    ///
+    /// Handlers belonging to a controller wrapped with [`TimeConstrainedOnOff::from_onoff`] are
+    /// not visible to this controller and are not reported.
+    pub fn has_off_handler(&self) -> bool {
+        self.handle_off.is_some() || self.unified_handler.is_some()
+    }
+
+    /// convienence method for checking if the controller is in the `on` state, which is `false`
+    /// while the state is unknown
+    #[inline]
+    pub fn is_on(&self) -> bool {
+        self.known && self.bang_bang.is_on()
+    }
+
+    /// convienence method for checking if the controller is in the `off` state, which is `false`
+    /// while the state is unknown
+    #[inline]
+    pub fn is_off(&self) -> bool {
+        self.known && self.bang_bang.is_off()
+    }
+
+    /// whether the controller has transitioned since it was created, including forced transitions
+    /// and resets but not blocked or vetoed attempts
+    pub fn has_transitioned(&self) -> bool {
+        // every successful transition is recorded, and the history is never cleared
+        self.history.iter().next().is_some()
+    }
+
+    /// current state, or [`Tristate::Unknown`] for a controller created with
+    /// [`TimeConstrainedOnOff::new_unknown`] that has not yet been set
+    pub fn tristate(&self) -> Tristate {
+        match (self.known, self.bang_bang.state()) {
+            (false, _) => Tristate::Unknown,
+            (true, BangBangState::A) => Tristate::Off,
+            (true, BangBangState::B) => Tristate::On,
+        }
+    }
+
+    /// minimum time constraint on leaving the current state, which is `minimum_on` when `on` and
+    /// `minimum_off` when `off`, including any extension from
+    /// [`TimeConstrainedOnOff::with_proportional_off`]
+    ///
+    /// This is the value transitions are checked against, before any tolerance is deducted.
+    pub fn active_minimum(&self) -> Option<Duration> {
+        match self.state() {
+            // `None` orders before `Some`, so this is the longer of the two if either is set
+            BangBangState::A => self.minimum_off.max(self.dynamic_minimum_off),
+            BangBangState::B => self.minimum_on,
+        }
+    }
+
+    /// multiplies both minimum time constraints by `factor`, for example to speed up a
+    /// commissioning sweep, until [`TimeConstrainedOnOff::restore_minimums`] is called
+    ///
+    /// The minimums in effect at the first call are kept and each call scales those, so scaling
+    /// twice does not compound. Results saturate at the range of the clock, while negative or
+    /// `NaN` factors remove the minimums by scaling them to zero.
+    pub fn scale_minimums(&mut self, factor: f32) {
+        let (minimum_on, minimum_off) = *self
+            .unscaled_minimums
+            .get_or_insert((self.minimum_on, self.minimum_off));
+
+        self.minimum_on = clamp_to_clock(
+            "minimum_on",
+            minimum_on.map(|minimum| scale_duration(minimum, factor)),
+        );
+        self.minimum_off = clamp_to_clock(
+            "minimum_off",
+            minimum_off.map(|minimum| scale_duration(minimum, factor)),
+        );
+    }
+
+    /// returns the minimum time constraints to the values they had before the first call to
+    /// [`TimeConstrainedOnOff::scale_minimums`], if they are scaled
+    ///
+    /// A minimum set directly while scaled, such as with [`TimeConstrainedOnOff::set_minimum_on`],
+    /// takes effect unscaled and is the value restored here, replacing the one kept by the first
+    /// call to [`TimeConstrainedOnOff::scale_minimums`].
+    pub fn restore_minimums(&mut self) {
+        if let Some((minimum_on, minimum_off)) = self.unscaled_minimums.take() {
+            self.minimum_on = minimum_on;
+            self.minimum_off = minimum_off;
+        }
+    }
+
+    /// minimum time the controller must remain `on` before it may transition to `off`
+    pub fn minimum_on(&self) -> Option<Duration> {
+        self.minimum_on
+    }
+
+    /// changes the minimum time the controller must remain `on` before it may transition to `off`
+    pub fn set_minimum_on(&mut self, minimum_on: Option<Duration>) {
+        self.minimum_on = clamp_to_clock("minimum_on", minimum_on);
+        if let Some((unscaled, _)) = self.unscaled_minimums.as_mut() {
+            *unscaled = self.minimum_on;
+        }
+    }
+
+    /// minimum time the controller must remain `off` before it may transition to `on`
+    pub fn minimum_off(&self) -> Option<Duration> {
+        self.minimum_off
+    }
+
+    /// changes the minimum time the controller must remain `off` before it may transition to `on`
+    pub fn set_minimum_off(&mut self, minimum_off: Option<Duration>) {
+        self.minimum_off = clamp_to_clock("minimum_off", minimum_off);
+        if let Some((_, unscaled)) = self.unscaled_minimums.as_mut() {
+            *unscaled = self.minimum_off;
+        }
+    }
+
+    /// changes both minimum time constraints in one call, and when `rebase` is `true` restarts the
+    /// minimum time constraint of the current state as though it had just been entered
+    ///
+    /// Rebasing discards any time credited by [`TimeConstrainedOnOff::prime`] and any active time
+    /// added with [`TimeConstrainedOnOff::add_active_time`], as [`TimeConstrainedOnOff::start`]
+    /// does, but does not record a transition or call a handler.
+    pub fn apply_config(
+        &mut self,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        rebase: bool,
+    ) {
+        self.set_minimum_on(minimum_on);
+        self.set_minimum_off(minimum_off);
+
+        if rebase {
+            self.last_changed = self.read_clock(self.state());
+            self.active_time = 0;
+            self.primed = 0;
+        }
+    }
+
+    /// changes the minimum time constraints of both states to the same value
+    pub fn set_minimum_both(&mut self, minimum: Option<Duration>) {
+        self.set_minimum_on(minimum);
+        self.set_minimum_off(minimum);
+    }
+
+    /// maximum time the controller may remain `on` before [`TimeConstrainedOnOff::poll`] forces
+    /// it `off`
+    pub fn maximum_on(&self) -> Option<Duration> {
+        self.maximum_on
+    }
+
+    /// changes the maximum time the controller may remain `on` before
+    /// [`TimeConstrainedOnOff::poll`] forces it `off`
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `maximum_on` is shorter than the minimum `on` time, use
+    /// [`TimeConstrainedOnOff::validate`] to check a configuration without panicking.
+    pub fn set_maximum_on(&mut self, maximum_on: Option<Duration>) {
+        self.maximum_on = clamp_to_clock("maximum_on", maximum_on);
+        debug_assert_maximum("on", self.minimum_on, self.maximum_on);
+    }
+
+    /// maximum time the controller may remain `off` before [`TimeConstrainedOnOff::poll`] forces
+    /// it `on`
+    pub fn maximum_off(&self) -> Option<Duration> {
+        self.maximum_off
+    }
+
+    /// changes the maximum time the controller may remain `off` before
+    /// [`TimeConstrainedOnOff::poll`] forces it `on`
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `maximum_off` is shorter than the minimum `off` time, use
+    /// [`TimeConstrainedOnOff::validate`] to check a configuration without panicking.
+    pub fn set_maximum_off(&mut self, maximum_off: Option<Duration>) {
+        self.maximum_off = clamp_to_clock("maximum_off", maximum_off);
+        debug_assert_maximum("off", self.minimum_off, self.maximum_off);
+    }
+
+    /// minimum time that must pass before the controller may turn `on`, which is the same value
+    /// as [`TimeConstrainedOnOff::minimum_off`]
+    pub fn min_before_on(&self) -> Option<Duration> {
+        self.minimum_off()
+    }
+
+    /// changes the minimum time that must pass before the controller may turn `on`, which is the
+    /// same as calling [`TimeConstrainedOnOff::set_minimum_off`]
+    pub fn set_min_before_on(&mut self, minimum: Option<Duration>) {
+        self.set_minimum_off(minimum)
+    }
+
+    /// minimum time that must pass before the controller may turn `off`, which is the same value
+    /// as [`TimeConstrainedOnOff::minimum_on`]
+    pub fn min_before_off(&self) -> Option<Duration> {
+        self.minimum_on()
+    }
+
+    /// changes the minimum time that must pass before the controller may turn `off`, which is the
+    /// same as calling [`TimeConstrainedOnOff::set_minimum_on`]
+    pub fn set_min_before_off(&mut self, minimum: Option<Duration>) {
+        self.set_minimum_on(minimum)
+    }
+
+    /// enables or disables the `trace` level log record emitted every time elapsed time is
+    /// measured, independently of the level filter of the logger (enabled by default)
+    ///
+    /// Clock warnings are not affected. Useful when the controller is checked at a high rate and
+    /// the level filter cannot be changed.
+    #[cfg(feature = "log")]
+    pub fn set_log_timing(&mut self, enabled: bool) {
+        self.log_timing = enabled;
+    }
+
+    /// stops enforcing the minimum time constraints without forgetting their configured values
+    pub fn suspend_constraints(&mut self) {
+        #[cfg(feature = "log")]
+        debug!("suspending time constraints of {:?}", self);
+
+        self.constraints_suspended = true;
+    }
+
+    /// resumes enforcing the configured minimum time constraints after
+    /// [`TimeConstrainedOnOff::suspend_constraints`]
+    ///
+    /// Constraints are measured from the last transition, including any made while suspended.
+    pub fn resume_constraints(&mut self) {
+        #[cfg(feature = "log")]
+        debug!("resuming time constraints of {:?}", self);
+
+        self.constraints_suspended = false;
+    }
+
+    /// whether the minimum time constraints are currently being enforced
+    pub fn constraints_active(&self) -> bool {
+        !self.constraints_suspended
+    }
+
+    /// time left before the minimum time constraint of the current state is satisfied, or `None`
+    /// if a transition is permitted right now
+    #[inline]
+    #[must_use]
+    pub fn time_remaining(&self) -> Option<Duration> {
+        self.remaining_at(self.read_clock(self.state()))
+    }
+
+    /// time left before the maximum time of the current state forces a transition, or `None` if
+    /// the current state has no maximum
+    ///
+    /// Zero once the maximum has been reached but [`TimeConstrainedOnOff::poll`] has not yet
+    /// forced the transition.
+    #[must_use]
+    pub fn time_until_forced(&self) -> Option<Duration> {
+        let maximum = self.maximum_for(self.state())?;
+        let elapsed = self.elapsed_in_state(self.read_clock(self.state()));
+
+        Some(maximum.saturating_sub(Duration::from_millis(u64::from(elapsed))))
+    }
+
+    /// time spent in the current state so far, as measured for the minimum time constraints
+    #[must_use]
+    pub fn time_in_state(&self) -> Duration {
+        let elapsed = self.elapsed_in_state(self.read_clock(self.state()));
+        Duration::from_millis(u64::from(elapsed))
+    }
+
+    /// clock reading at which a transition out of the current state will be permitted, which is
+    /// the current reading if one is permitted right now
+    ///
+    /// Wraps around past `u32::MAX` along with the clock.
+    #[must_use]
+    pub fn next_allowed_at(&self) -> u32 {
+        let now = self.read_clock(self.state());
+        let remaining = self.remaining_at(now).map_or(0, saturating_millis);
+        now.wrapping_add(remaining)
+    }
+
+    /// orders controllers by [`TimeConstrainedOnOff::next_allowed_at`], readiest first, for
+    /// keeping them in a scheduling queue
+    ///
+    /// Readings are compared as points on a wrapping clock, so a reading just past the wrap sorts
+    /// after one just before it. Both controllers are expected to share a clock.
+    #[must_use]
+    pub fn cmp_ready(&self, other: &Self) -> Ordering {
+        // reinterpreting the wrapped difference as signed gives the shorter way around the clock
+        (self.next_allowed_at().wrapping_sub(other.next_allowed_at()) as i32).cmp(&0)
+    }
+
+    /// time left before the controller may turn `on` and before it may turn `off`, computed from a
+    /// single clock reading so the pair is always consistent
+    ///
+    /// Only the transition out of the current state can be blocked, so at most one of the two is
+    /// `Some`.
+    #[must_use]
+    pub fn remaining(&self) -> (Option<Duration>, Option<Duration>) {
+        let remaining = self.time_remaining();
+
+        match self.state() {
+            BangBangState::A => (remaining, None),
+            BangBangState::B => (None, remaining),
+        }
+    }
+
+    /// time elapsed on the `now` method since `timestamp`, a reading taken from the same clock
+    ///
+    /// The clock is assumed to have wrapped around past `u32::MAX` whenever it reads less than
+    /// `timestamp`, regardless of the clock policy, so `timestamp` must be less than one full
+    /// period of the clock old.
+    pub fn elapsed_since(&self, timestamp: u32) -> Duration {
+        Duration::from_millis(u64::from((self.now)().wrapping_sub(timestamp)))
+    }
+
+    /// state and time remaining packed into a single `u32` for compact telemetry, see
+    /// [`unpack_status`] for the layout
+    pub fn pack_status(&self) -> u32 {
+        let remaining = self.time_remaining().map_or(0, saturating_millis);
+        let on = if self.is_on() { STATUS_ON_BIT } else { 0 };
+
+        on | remaining.min(STATUS_REMAINING_MASK)
+    }
+
+    /// health of the controller as a register-style byte, see [`StatusFlags`] for the bits
+    pub fn status_flags(&self) -> StatusFlags {
+        let vetoed = matches!(self.last_error, Some(TransitionError::Vetoed(_)));
+
+        StatusFlags::empty()
+            .set_if(StatusFlags::ON, self.is_on())
+            .set_if(StatusFlags::CONSTRAINED, self.time_remaining().is_some())
+            .set_if(StatusFlags::VETOED, vetoed)
+            .set_if(StatusFlags::CLOCK_STALLED, self.clock_stalled())
+            .set_if(StatusFlags::UNKNOWN, !self.known)
+    }
+
+    /// counters and gauges describing the controller since creation, see [`Metrics`]
+    pub fn metrics(&self) -> Metrics {
+        let (on, off) = self.cumulative_time();
+        let duty_cycle = match on.saturating_add(off) {
+            0 => self.mapped(1.0, 0.0),
+            total => on as f32 / total as f32,
+        };
+
+        Metrics {
+            transitions: self.transitions,
+            on: self.is_on(),
+            duty_cycle,
+            blocked_attempts: self.blocked_attempts,
+        }
+    }
+
+    /// number of transitions that occurred within the trailing `window`, as measured by the `now`
+    /// method
+    ///
+    /// Only the most recent [`HISTORY_CAPACITY`] transitions are retained, so the count saturates
+    /// at that value.
+    pub fn transitions_within(&self, window: Duration) -> u32 {
+        let now = (self.now)();
+
+        self.history
+            .iter()
+            .filter(|&(timestamp, _)| {
+                let elapsed = self.time_delta(timestamp, now);
+                Duration::from_millis(u64::from(elapsed)) < window
+            })
+            .count() as u32
+    }
+
+    /// writes the retained transition history to `writer`, oldest first, one `timestamp,state`
+    /// line per transition where the state is `on` or `off`
+    ///
+    /// Works with any [`fmt::Write`] sink without allocating, such as a UART driver or a
+    /// fixed-capacity string.
+    pub fn export_history<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        for (timestamp, state) in self.history.iter().rev() {
+            let state = match state {
+                BangBangState::A => "off",
+                BangBangState::B => "on",
+            };
+            writeln!(writer, "{},{}", timestamp, state)?;
+        }
+
+        Ok(())
+    }
+
+    /// the most recent `N` retained transitions as `(timestamp, state)` pairs, oldest first, in a
+    /// fixed-capacity vector that never allocates
+    #[cfg(feature = "heapless")]
+    pub fn history_vec<const N: usize>(&self) -> heapless::Vec<(u32, BangBangState), N> {
+        let mut transitions = heapless::Vec::new();
+        for transition in self.history.iter().take(N) {
+            // taking at most `N` leaves room for every push
+            let _ = transitions.push(transition);
+        }
+        transitions.reverse();
+        transitions
+    }
+
+    /// time the controller most recently transitioned into the `on` state, as measured by the
+    /// clock of that state, or `None` if it never has
+    ///
+    /// Starting out `on` is not a transition and is not reported.
+    pub fn last_on_at(&self) -> Option<u32> {
+        self.last_on_at
+    }
+
+    /// time the controller most recently transitioned into the `off` state, as measured by the
+    /// clock of that state, or `None` if it never has
+    ///
+    /// Starting out `off` is not a transition and is not reported.
+    pub fn last_off_at(&self) -> Option<u32> {
+        self.last_off_at
+    }
+
+    /// marks the start of a reporting interval for [`TimeConstrainedOnOff::duty_since_checkpoint`]
+    pub fn checkpoint(&mut self) {
+        self.checkpoint = self.cumulative_time();
+    }
+
+    /// fraction of the time since the last [`TimeConstrainedOnOff::checkpoint`], or since
+    /// creation, spent in the `on` state
+    ///
+    /// Unlike [`TimeConstrainedOnOff::on_ratio_over`] this is not limited by the retained
+    /// history. When no time has passed the current state is reported.
+    pub fn duty_since_checkpoint(&self) -> f32 {
+        let (on, off) = self.cumulative_time();
+        let on = on.saturating_sub(self.checkpoint.0);
+        let total = on + off.saturating_sub(self.checkpoint.1);
+
+        if total == 0 {
+            return self.mapped(1.0, 0.0);
+        }
+
+        on as f32 / total as f32
+    }
+
+    /// milliseconds spent `on` and `off` since creation, including the current state so far
+    fn cumulative_time(&self) -> (u64, u64) {
+        let now = self.read_clock(self.state());
+        let current = self.time_since_entered(now);
+
+        match self.state() {
+            BangBangState::A => (self.time_on, self.time_off.saturating_add(current)),
+            BangBangState::B => (self.time_on.saturating_add(current), self.time_off),
+        }
+    }
+
+    /// fraction of the trailing `window` spent in the `on` state, as measured by the `now` method
+    ///
+    /// The window is reconstructed from the transition history. Time before the oldest retained
+    /// transition is assumed to have been spent in the opposite state to the one it entered, or
+    /// in the current state when there is no history. An empty window reports the current state.
+    pub fn on_ratio_over(&self, window: Duration) -> f32 {
+        let window = saturating_millis(window);
+        if window == 0 {
+            return self.mapped(1.0, 0.0);
+        }
+
+        let now = (self.now)();
+        let mut on = 0;
+        let mut covered = 0;
+        let mut earlier_state = self.state();
+
+        for (timestamp, state) in self.history.iter() {
+            let age = self.time_delta(timestamp, now).min(window);
+            if state == BangBangState::B {
+                on += age.saturating_sub(covered);
+            }
+            covered = covered.max(age);
+            earlier_state = opposite(state);
+
+            if covered == window {
+                break;
+            }
+        }
+        if earlier_state == BangBangState::B {
+            on += window - covered;
+        }
+
+        on as f32 / window as f32
+    }
+
+    /// time since a transition was last blocked by a time constraint, or `None` if no transition
+    /// has been blocked since the last successful transition
+    ///
+    /// Every successful transition clears the record, so this measures how long the current
+    /// streak of blocked attempts has been going on, which suits retry backoff strategies.
+    pub fn since_last_blocked(&self) -> Option<Duration> {
+        let last_blocked_at = self.last_blocked_at?;
+        let elapsed = self.time_delta(last_blocked_at, self.read_clock(self.state()));
+
+        Some(Duration::from_millis(u64::from(elapsed)))
+    }
+
+    /// number of transitions blocked by a time constraint since creation or the last call to
+    /// [`TimeConstrainedOnOff::reset_blocked_attempt_count`], saturating at `u32::MAX`
+    ///
+    /// Handler vetoes and requests for the state the controller is already in are not counted.
+    pub fn blocked_attempt_count(&self) -> u32 {
+        self.blocked_attempts
+    }
+
+    /// restarts the count reported by [`TimeConstrainedOnOff::blocked_attempt_count`] from zero
+    pub fn reset_blocked_attempt_count(&mut self) {
+        self.blocked_attempts = 0;
+    }
+
+    /// continues the transition and blocked attempt counts of [`TimeConstrainedOnOff::metrics`]
+    /// from values persisted before a restart
+    pub fn restore_counters(&mut self, transitions: u64, blocked_attempts: u32) {
+        self.transitions = transitions;
+        self.restored_transitions = (transitions, self.history.recorded());
+        self.blocked_attempts = blocked_attempts;
+    }
+
+    /// number of times a transition or poll has read the clock running backwards, that is reading
+    /// less than the previous transition or poll without having wrapped around, saturating at
+    /// `u32::MAX`
+    pub fn backward_jump_count(&self) -> u32 {
+        self.backward_jumps
+    }
+
+    /// whether the transition, blocked attempt or backward jump count has reached its ceiling
+    ///
+    /// The counts saturate rather than wrap, so a saturated count stops advancing until it is
+    /// reset or restored to a lower value.
+    pub fn counters_saturated(&self) -> bool {
+        self.transitions == u64::MAX
+            || self.blocked_attempts == u32::MAX
+            || self.backward_jumps == u32::MAX
+    }
+
+    /// whether the settling time configured with [`TimeConstrainedOnOff::with_settling`] has passed
+    /// since the last transition, so the commanded state is taken to be physically achieved
+    #[must_use]
+    pub fn is_settled(&self) -> bool {
+        self.settling_remaining(self.read_clock(self.state()))
+            .is_none()
+    }
+
+    /// whether stall detection is enabled and the clock has read the same time for the configured
+    /// number of consecutive blocked transitions
+    ///
+    /// Cleared by the next blocked transition that reads a different time, or by a transition.
+    #[must_use]
+    pub fn clock_stalled(&self) -> bool {
+        match self.stall_threshold {
+            Some(threshold) => self.identical_blocked_readings >= threshold,
+            None => false,
+        }
+    }
+
+    /// reports the state that has become available to transition to, once, the first time it is
+    /// called after the minimum time constraint of the current state is satisfied
+    ///
+    /// This is edge-triggered: further calls return `None` until the controller transitions and
+    /// the constraint of the new state is in turn satisfied.
+    #[must_use]
+    pub fn poll_ready(&mut self) -> Option<BangBangState> {
+        let now = self.read_clock(self.state());
+        self.observe_ready(now)
+    }
+
+    /// detects the edge reported by [`TimeConstrainedOnOff::poll_ready`], calling the on ready
+    /// callback when it is found
+    fn observe_ready(&mut self, now: u32) -> Option<BangBangState> {
+        let ready = self.remaining_at(now).is_none();
+        let became_ready = ready && !self.was_ready;
+        self.was_ready = ready;
+
+        if !became_ready {
+            return None;
+        }
+
+        let state = opposite(self.state());
+        if let Some(callback) = self.on_ready.as_mut() {
+            callback(state);
+        }
+
+        Some(state)
+    }
+
+    /// time left at `now` before the minimum constraint of the current state is satisfied, if any
+    #[inline]
+    fn remaining_at(&self, now: u32) -> Option<Duration> {
+        if self.constraints_suspended || !self.started {
+            return None;
+        }
+
+        let enforced = match self.minimum_condition {
+            Some(condition) => condition(),
+            None => true,
+        };
+        let minimum_remaining = self
+            .active_minimum()
+            .filter(|_| enforced)
+            .and_then(|minimum| {
+                let mut min_duration = minimum.saturating_sub(self.tolerance);
+                if self.boundary_raised {
+                    min_duration = min_duration.saturating_add(self.timing_hysteresis);
+                }
+                if self.boundary_policy == BoundaryPolicy::Exclusive {
+                    // the millisecond clock cannot tell apart anything finer
+                    min_duration = min_duration.saturating_add(Duration::from_millis(1));
+                }
+                let time_delta = Duration::from_millis(u64::from(self.elapsed_in_state(now)));
+
+                min_duration.checked_sub(time_delta)
+            });
+
+        match (minimum_remaining, self.settling_remaining(now)) {
+            (Some(minimum), Some(settling)) => Some(minimum.max(settling)),
+            (remaining, None) | (None, remaining) => remaining,
+        }
+        .filter(|remaining| *remaining > Duration::from_millis(0))
+    }
+
+    /// time left before the current state has settled as of `now`, if any
+    fn settling_remaining(&self, now: u32) -> Option<Duration> {
+        if !self.started {
+            return None;
+        }
+
+        let settled = Duration::from_millis(self.time_since_entered(now));
+        self.settling
+            .checked_sub(settled)
+            .filter(|remaining| *remaining > Duration::from_millis(0))
+    }
+
+    /// milliseconds spent in the current state as of `now`, including any primed time
+    #[inline]
+    fn elapsed_in_state(&self, now: u32) -> u32 {
+        let elapsed = if !self.started {
+            0
+        } else if self.gated {
+            self.active_time
+        } else {
+            self.time_delta(self.last_changed, now)
+        };
+
+        elapsed.saturating_add(self.primed)
+    }
+
+    /// milliseconds spent in the current state as of `now` regardless of the timer reset policy,
+    /// which is none before the timer has started
+    fn time_since_entered(&self, now: u32) -> u64 {
+        if self.started {
+            u64::from(self.time_delta(self.entered_at, now))
+        } else {
+            0
+        }
+    }
+
+    /// milliseconds from `prior` to `later` according to the clock policy
+    #[inline]
+    fn time_delta(&self, prior: u32, later: u32) -> u32 {
+        let escalate = self
+            .backward_jump_threshold
+            .is_some_and(|threshold| self.backward_jumps >= threshold);
+        let time_delta = assess_time_delta(prior, later, self.clock_policy, escalate);
+
+        #[cfg(feature = "log")]
+        if self.log_timing {
+            trace!(
+                "time delta from {}ms to {}ms is {}ms",
+                prior,
+                later,
+                time_delta
+            );
+        }
+
+        time_delta
+    }
+
+    #[inline]
+    fn record_handler_duration(&mut self, new_state: BangBangState, started: u32) {
+        let finished = self.read_clock(self.state());
+        let took = Some(Duration::from_millis(u64::from(
+            self.time_delta(started, finished),
+        )));
+
+        match new_state {
+            BangBangState::A => self.last_off_handler_duration = took,
+            BangBangState::B => self.last_on_handler_duration = took,
+        }
+    }
+
+    /// counts a backward jump when `now` is less than the previous reading seen by a transition
+    /// or poll, so a clock that stays behind is only counted once
+    #[inline]
+    fn observe_clock(&mut self, now: u32) {
+        if let Some(previous) = self.last_reading {
+            // a drop of more than half the range of the clock is the counter wrapping around
+            if (now.wrapping_sub(previous) as i32) < 0 {
+                self.backward_jumps = self.backward_jumps.saturating_add(1);
+            }
+        }
+        self.last_reading = Some(now);
+    }
+
+    /// counts consecutive blocked transitions that read the same time, for stall detection
+    fn observe_blocked_reading(&mut self, now: u32) {
+        self.identical_blocked_readings = match self.last_blocked_at {
+            Some(last_blocked_at) if last_blocked_at == now => {
+                self.identical_blocked_readings.saturating_add(1)
+            }
+            _ => 1,
+        };
+
+        #[cfg(feature = "log")]
+        if Some(self.identical_blocked_readings) == self.stall_threshold {
+            warn!(
+                "{:?} blocked {} times in a row at {}ms, the clock appears to be stalled",
+                self, self.identical_blocked_readings, now
+            );
+        }
+    }
+
+    /// reads the clock that measures time spent in `state`
+    #[inline]
+    fn read_clock(&self, state: BangBangState) -> u32 {
+        match (state, self.off_clock) {
+            (BangBangState::A, Some(off_clock)) => off_clock(),
+            _ => (self.now)(),
+        }
+    }
+
+    #[inline]
+    fn maximum_for(&self, state: BangBangState) -> Option<Duration> {
+        match state {
+            BangBangState::A => self.maximum_off,
+            BangBangState::B => self.maximum_on,
+        }
+    }
+
+    /// enforced minimum of the current state if the time measured by the clock since the timer
+    /// last restarted does not cover it as of `now`, independently of how constraints are checked
+    ///
+    /// Gated active time is deliberately not used, so crediting more active time than actually
+    /// passed shows up here.
+    fn unmet_minimum(&self, now: u32) -> Option<Duration> {
+        let enforced = match self.minimum_condition {
+            Some(condition) => condition(),
+            None => true,
+        };
+        if self.constraints_suspended || !self.started || !enforced {
+            return None;
+        }
+
+        let minimum = match self.state() {
+            BangBangState::A => self.minimum_off.max(self.dynamic_minimum_off),
+            BangBangState::B => self.minimum_on,
+        }?;
+        let elapsed = self
+            .time_delta(self.last_changed, now)
+            .saturating_add(self.primed);
+
+        if Duration::from_millis(u64::from(elapsed)) < minimum.saturating_sub(self.tolerance) {
+            Some(minimum)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn change_state(&mut self, new_state: BangBangState) -> Result<(), BangBangError> {
+        let started = if self.handler_timing {
+            Some(self.read_clock(self.state()))
+        } else {
+            None
+        };
+
+        let result = match (new_state, self.unified_handler.as_mut()) {
+            (_, Some(handler)) => Some(handler(new_state)),
+            (BangBangState::A, None) => self.handle_off.as_mut().map(|handler| handler()),
+            (BangBangState::B, None) => self.handle_on.as_mut().map(|handler| handler()),
+        };
+        if let Some(result) = result {
+            if let Some(started) = started {
+                self.record_handler_duration(new_state, started);
+            }
+            match result {
+                Err(error) if self.handler_is_advisory => {
+                    #[cfg(feature = "log")]
+                    warn!(
+                        "handler for {:?} failed with {:?}, transitioning anyway as it is advisory",
+                        new_state, error
+                    );
+
+                    self.last_error = Some(TransitionError::Vetoed(error));
+                }
+                result => result?,
+            }
+        }
+
+        self.bang_bang.set(new_state)?;
+
+        debug_assert_eq!(
+            self.state(),
+            new_state,
+            "inner controller accepted a transition to {:?} but did not change state",
+            new_state
+        );
+
+        Ok(())
+    }
+
+    #[inline]
+    fn mark_changed(&mut self, from: BangBangState, now: u32) {
+        self.dynamic_minimum_off = match (from, self.state(), self.proportional_off) {
+            (BangBangState::B, BangBangState::A, Some(factor)) => {
+                // the timer may not have restarted on entering `on`, so measure from the entry
+                let on_duration = self.time_since_entered(now);
+                // float to integer casts saturate, so this can neither wrap nor panic
+                let minimum = (on_duration as f32 * factor) as u32;
+                Some(Duration::from_millis(u64::from(minimum)))
+            }
+            _ => None,
+        };
+
+        let spent = self.time_since_entered(now);
+        match from {
+            BangBangState::A => self.time_off = self.time_off.saturating_add(spent),
+            BangBangState::B => self.time_on = self.time_on.saturating_add(spent),
+        }
+
+        // with dual clocks `now` is a reading of the clock being left, so read the one entered
+        let entered_at = match self.off_clock {
+            Some(_) => self.read_clock(self.state()),
+            None => now,
+        };
+
+        if !self.started || self.timer_reset_policy.resets(from, self.state()) {
+            if self.started {
+                self.previous_changed = Some(self.last_changed);
+            }
+            self.last_changed = entered_at;
+            self.active_time = 0;
+        }
+        self.entered_at = entered_at;
+        match self.state() {
+            BangBangState::A => self.last_off_at = Some(entered_at),
+            BangBangState::B => self.last_on_at = Some(entered_at),
+        }
+        self.transitions = self.transitions.saturating_add(1);
+        self.started = true;
+        self.known = true;
+        self.boundary_raised = false;
+        self.minimum_bypassed = false;
+        self.primed = 0;
+        self.last_blocked_at = None;
+        self.identical_blocked_readings = 0;
+        self.was_ready = false;
+        self.history.push(entered_at, self.state());
+        if let Some(reader) = self.reader {
+            reader.publish(self.is_on());
+        }
+    }
+
+    /// whether a transition to `new_state` is permitted as of `now`, which is `true` when it is
+    /// only permitted because the fallback chose to force it
+    #[inline]
+    fn check_constraint(
+        &mut self,
+        now: u32,
+        new_state: BangBangState,
+    ) -> Result<bool, TransitionError> {
+        if self.remaining_at(now).is_none() {
+            return Ok(false);
+        }
+
+        // the timing hysteresis applies from this block on, including to the remaining time
+        self.boundary_raised = true;
+        let remaining = self.remaining_at(now).unwrap_or_default();
+
+        let decision = match self.fallback.take() {
+            Some(fallback) => {
+                let decision = fallback(self);
+                self.fallback = Some(fallback);
+                decision
+            }
+            None => Decision::Wait,
+        };
+
+        #[cfg(feature = "log")]
+        debug!(
+            "transition from {:?} to {:?} constrained, decision is {:?}",
+            self.state(),
+            new_state,
+            decision
+        );
+
+        let from = self.state();
+        let error = match decision {
+            Decision::Force => return Ok(true),
+            Decision::Wait => TransitionError::Constrained {
+                from,
+                to: new_state,
+                remaining,
+            },
+            Decision::Abort => TransitionError::Aborted {
+                from,
+                to: new_state,
+            },
+        };
+
+        // asking for the current state is not an attempt, so it counts neither way
+        if new_state != from {
+            self.blocked_attempts = self.blocked_attempts.saturating_add(1);
+            self.observe_blocked_reading(now);
+            self.last_blocked_at = Some(now);
+        }
+
+        Err(error)
+    }
+}
+
+/// shortest [`TimeConstrainedOnOff::time_remaining`] of the provided controllers, ignoring those
+/// that could transition right now, or `None` if every controller could transition right now
+///
+/// Useful for computing how long an event loop can sleep before any controller could next change.
+pub fn soonest_allowed(controllers: &[&TimeConstrainedOnOff<'_>]) -> Option<Duration> {
+    controllers
+        .iter()
+        .filter_map(|controller| controller.time_remaining())
+        .min()
+}
+
+/// ordered list of handlers called one after another, stopping at the first one that vetoes
+///
+/// Handlers passed to a controller must be `'static`, so a chain borrowing `'static` handlers,
+/// such as ones held in `static` items, is installed by moving it into a handler that calls
+/// [`HandlerChain::call`]. A chain of shorter lived handlers can still be called from within a
+/// handler that owns it.
+pub struct HandlerChain<'h, 'c> {
+    handlers: &'h mut [&'c mut StateChangeHander],
+}
+
+impl fmt::Debug for HandlerChain<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HandlerChain {{ len: {} }}", self.handlers.len())
+    }
+}
+
+impl<'h, 'c> HandlerChain<'h, 'c> {
+    /// chains `handlers`, which are called in order
+    pub fn new(handlers: &'h mut [&'c mut StateChangeHander]) -> Self {
+        Self { handlers }
+    }
+
+    /// number of handlers in the chain
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// whether the chain has no handlers, in which case calling it always succeeds
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// calls each handler in order, returning the first error so that it vetoes the transition
+    /// without calling the handlers after it
+    pub fn call(&mut self) -> Result<(), BangBangError> {
+        self.handlers.iter_mut().try_for_each(|handler| handler())
+    }
+}
+
+/// splits a status packed by [`TimeConstrainedOnOff::pack_status`] into whether the controller was
+/// `on` and the milliseconds remaining before it could transition
+///
+/// The most significant bit is set when `on`, and the lower 31 bits hold the remaining time in
+/// milliseconds, clamped at `0x7FFF_FFFF`, roughly 24.8 days. Zero means a transition was permitted.
+pub fn unpack_status(status: u32) -> (bool, u32) {
+    (status & STATUS_ON_BIT != 0, status & STATUS_REMAINING_MASK)
+}
+
+const STATUS_ON_BIT: u32 = 1 << 31;
+const STATUS_REMAINING_MASK: u32 = !STATUS_ON_BIT;
+
+/// clamps constraints that the `u32` millisecond clock can never measure to the longest it can,
+/// logging a warning, so that they are not left unsatisfiable
+#[cfg_attr(not(feature = "log"), allow(unused_variables))]
+fn clamp_to_clock(name: &str, constraint: Option<Duration>) -> Option<Duration> {
+    let longest = Duration::from_millis(u64::from(u32::MAX));
+
+    match constraint {
+        Some(constraint) if exceeds_clock_range(Some(constraint)) => {
+            #[cfg(feature = "log")]
+            warn!(
+                "{} of {:?} exceeds the range of the u32 millisecond clock, clamping to {:?}",
+                name, constraint, longest
+            );
+
+            Some(longest)
+        }
+        _ => constraint,
+    }
+}
+
+/// panics in debug builds when the maximum time of the `state` named is shorter than its minimum,
+/// as the maximum would then force every transition before the minimum permits one
+#[inline]
+fn debug_assert_maximum(state: &str, minimum: Option<Duration>, maximum: Option<Duration>) {
+    if let (Some(minimum), Some(maximum)) = (minimum, maximum) {
+        debug_assert!(
+            minimum <= maximum,
+            "maximum {} time of {:?} is shorter than the minimum {} time of {:?}",
+            state,
+            maximum,
+            state,
+            minimum
+        );
+    }
+}
+
+/// whether `constraint` is longer than the `u32` millisecond clock can measure
+fn exceeds_clock_range(constraint: Option<Duration>) -> bool {
+    constraint.is_some_and(|constraint| constraint > Duration::from_millis(u64::from(u32::MAX)))
+}
+
+/// `duration` multiplied by `factor`, saturating at the longest representable duration and
+/// flooring negative or `NaN` results at zero
+fn scale_duration(duration: Duration, factor: f32) -> Duration {
+    let scaled = duration.as_secs_f64() * f64::from(factor);
+    if scaled.is_nan() || scaled <= 0.0 {
+        return Duration::from_millis(0);
+    }
+
+    Duration::try_from_secs_f64(scaled).unwrap_or(Duration::MAX)
+}
+
+/// milliseconds in `duration`, saturating at the `u32` range of the clock
+fn saturating_millis(duration: Duration) -> u32 {
+    u32::try_from(duration.as_millis()).unwrap_or(u32::MAX)
+}
+
+#[inline]
+fn opposite(state: BangBangState) -> BangBangState {
+    match state {
+        BangBangState::A => BangBangState::B,
+        BangBangState::B => BangBangState::A,
+    }
+}
+
+#[inline]
+#[cfg_attr(not(feature = "log"), allow(unused_variables))]
+fn assess_time_delta(
+    prior_milliseconds: u32,
+    later_milliseconds: u32,
+    clock_policy: ClockPolicy,
+    escalate: bool,
+) -> u32 {
+    // the clock reads less than it did previously, interpret that according to the policy
+    if later_milliseconds < prior_milliseconds {
+        let time_delta = match clock_policy {
+            // assume that the delta can be only as large as the current value
+            ClockPolicy::Restarting => later_milliseconds,
+            ClockPolicy::Wrapping => later_milliseconds.wrapping_sub(prior_milliseconds),
+            ClockPolicy::Saturating | ClockPolicy::Monotonic => 0,
+        };
+
+        #[cfg(feature = "log")]
+        if escalate {
+            error!(
+                "time delta from {}ms to {}ms is negative and the clock keeps running backwards, the time source is likely faulty, delta is {}ms",
+                prior_milliseconds, later_milliseconds, time_delta
+            );
+            return time_delta;
+        }
+
+        #[cfg(feature = "log")]
+        match clock_policy {
+            ClockPolicy::Monotonic => warn!(
+                "time delta from {}ms to {}ms is negative on a monotonic clock, treating as clock error, delta is {}ms",
+                prior_milliseconds, later_milliseconds, time_delta
+            ),
+            _ => warn!(
+                "time delta from {}ms to {}ms is negative, assuming {:?} counter overrun, delta is {}ms",
+                prior_milliseconds, later_milliseconds, clock_policy, time_delta
+            ),
+        }
+
+        return time_delta;
+    };
+
+    later_milliseconds - prior_milliseconds
 }
@@ -0,0 +1,143 @@
+//! time constrained controller generic over the clock, measuring time without converting to
+//! `u32` milliseconds
+
+use crate::StateChangeHander;
+use bangbang::prelude::*;
+use core::fmt;
+use core::time::Duration;
+
+#[cfg(feature = "log")]
+use log::debug;
+
+/// source of monotonic time, such as `std::time::Instant`, a `fugit` instant or an RTC driver
+pub trait MonotonicClock {
+    /// point in time reported by this clock
+    type Instant: Copy;
+
+    /// the current time
+    fn now(&self) -> Self::Instant;
+
+    /// time passed from `earlier` to `later`, which is zero if `later` is not after `earlier`
+    fn duration_since(&self, later: Self::Instant, earlier: Self::Instant) -> Duration;
+}
+
+/// [`MonotonicClock`] backed by `std::time::Instant`
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdClock;
+
+#[cfg(feature = "std")]
+impl MonotonicClock for StdClock {
+    type Instant = std::time::Instant;
+
+    fn now(&self) -> Self::Instant {
+        std::time::Instant::now()
+    }
+
+    fn duration_since(&self, later: Self::Instant, earlier: Self::Instant) -> Duration {
+        later.saturating_duration_since(earlier)
+    }
+}
+
+/// on/off bang-bang controller that restricts how quickly states can be changed, measuring time
+/// with a [`MonotonicClock`]
+///
+/// A leaner counterpart of [`TimeConstrainedOnOff`](crate::TimeConstrainedOnOff) that stores the
+/// instant of the last transition as reported by the clock and compares [`Duration`]s directly,
+/// so there is no `u32` millisecond counter to wrap around or lose precision.
+pub struct InstantOnOff<'a, C: MonotonicClock> {
+    bang_bang: OnOff<'a>,
+    minimum_on: Option<Duration>,
+    minimum_off: Option<Duration>,
+    last_changed: C::Instant,
+    clock: C,
+}
+
+impl<C: MonotonicClock> fmt::Debug for InstantOnOff<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "InstantOnOff {{ on: {} }}", self.bang_bang.is_on())
+    }
+}
+
+impl<'a, C: MonotonicClock> InstantOnOff<'a, C> {
+    /// creates a new on/off controller with optional notification handlers for each state transition
+    pub fn new(
+        on: bool,
+        handle_on: Option<&'a mut StateChangeHander>,
+        handle_off: Option<&'a mut StateChangeHander>,
+        minimum_on: Option<Duration>,
+        minimum_off: Option<Duration>,
+        clock: C,
+    ) -> Self {
+        let on_off = Self {
+            bang_bang: OnOff::new(on, handle_on, handle_off),
+            minimum_on,
+            minimum_off,
+            last_changed: clock.now(),
+            clock,
+        };
+
+        #[cfg(feature = "log")]
+        debug!("instiantiated {:?}", &on_off);
+
+        on_off
+    }
+
+    /// time left before the minimum time constraint of the current state is satisfied, or `None`
+    /// if a transition is permitted right now
+    #[must_use]
+    pub fn time_remaining(&self) -> Option<Duration> {
+        self.remaining_at(self.clock.now())
+    }
+
+    /// convienence method for checking if the controller is in the `on` state
+    pub fn is_on(&self) -> bool {
+        self.bang_bang.is_on()
+    }
+
+    /// convienence method for checking if the controller is in the `off` state
+    pub fn is_off(&self) -> bool {
+        self.bang_bang.is_off()
+    }
+
+    /// the clock used to measure time
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    fn remaining_at(&self, now: C::Instant) -> Option<Duration> {
+        let min_duration = match self.state() {
+            BangBangState::A => self.minimum_off,
+            BangBangState::B => self.minimum_on,
+        }?;
+        let elapsed = self.clock.duration_since(now, self.last_changed);
+
+        if min_duration > elapsed {
+            Some(min_duration - elapsed)
+        } else {
+            None
+        }
+    }
+}
+
+impl<C: MonotonicClock> BangBang for InstantOnOff<'_, C> {
+    fn state(&self) -> BangBangState {
+        self.bang_bang.state()
+    }
+
+    fn set(&mut self, new_state: BangBangState) -> Result<(), BangBangError> {
+        let now = self.clock.now();
+        if self.remaining_at(now).is_some() {
+            return Err(BangBangError::StateChangeTemporarilyConstrained {
+                from: self.state(),
+                to: new_state,
+                code: 0,
+            });
+        }
+
+        self.bang_bang.set(new_state)?;
+        self.last_changed = now;
+
+        Ok(())
+    }
+}
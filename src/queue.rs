@@ -0,0 +1,154 @@
+//! fixed-capacity queue for deferring commands, such as from an interrupt handler to a main loop
+
+use crate::{opposite, Command, TimeConstrainedOnOff, TransitionError};
+use bangbang::prelude::*;
+use core::fmt;
+use heapless::spsc::{Consumer, Producer, Queue};
+
+#[cfg(feature = "log")]
+use log::debug;
+
+/// queue of pending [`Command`]s that are applied to a controller in the order they were enqueued
+///
+/// Backed by a [`heapless::spsc::Queue`], so it holds at most `N - 1` commands and never allocates.
+/// Use [`CommandQueue::split`] to fill the queue from one context, such as an interrupt handler,
+/// while draining it from another.
+#[derive(Debug)]
+pub struct CommandQueue<const N: usize> {
+    queue: Queue<Command, N>,
+}
+
+impl<const N: usize> Default for CommandQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> CommandQueue<N> {
+    /// creates an empty command queue
+    pub const fn new() -> Self {
+        Self {
+            queue: Queue::new(),
+        }
+    }
+
+    /// adds a command to the back of the queue, handing it back if the queue is full
+    pub fn enqueue(&mut self, command: Command) -> Result<(), Command> {
+        self.queue.enqueue(command)
+    }
+
+    /// number of commands waiting to be applied
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// whether there are no commands waiting to be applied
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// applies queued commands to `controller` as [`CommandConsumer::drain_into`] does
+    pub fn drain_into(
+        &mut self,
+        controller: &mut TimeConstrainedOnOff<'_>,
+    ) -> Result<(), TransitionError> {
+        self.split().1.drain_into(controller)
+    }
+
+    /// splits the queue into a producer and a consumer that can be used from different contexts
+    /// without locking, such as the producer from an interrupt handler and the consumer from the
+    /// main loop
+    ///
+    /// Splitting a queue with a `'static` lifetime, such as one held in a `static`, yields halves
+    /// that can be handed to interrupt handlers.
+    pub fn split(&mut self) -> (CommandProducer<'_, N>, CommandConsumer<'_, N>) {
+        let (producer, consumer) = self.queue.split();
+
+        (CommandProducer { producer }, CommandConsumer { consumer })
+    }
+}
+
+/// half of a split [`CommandQueue`] that adds commands
+pub struct CommandProducer<'q, const N: usize> {
+    producer: Producer<'q, Command, N>,
+}
+
+impl<const N: usize> fmt::Debug for CommandProducer<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CommandProducer {{ len: {} }}", self.producer.len())
+    }
+}
+
+impl<const N: usize> CommandProducer<'_, N> {
+    /// adds a command to the back of the queue, handing it back if the queue is full
+    pub fn enqueue(&mut self, command: Command) -> Result<(), Command> {
+        self.producer.enqueue(command)
+    }
+
+    /// whether there is room for another command
+    pub fn ready(&self) -> bool {
+        self.producer.ready()
+    }
+}
+
+/// half of a split [`CommandQueue`] that applies commands to a controller
+pub struct CommandConsumer<'q, const N: usize> {
+    consumer: Consumer<'q, Command, N>,
+}
+
+impl<const N: usize> fmt::Debug for CommandConsumer<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CommandConsumer {{ len: {} }}", self.consumer.len())
+    }
+}
+
+impl<const N: usize> CommandConsumer<'_, N> {
+    /// number of commands waiting to be applied
+    pub fn len(&self) -> usize {
+        self.consumer.len()
+    }
+
+    /// whether there are no commands waiting to be applied
+    pub fn is_empty(&self) -> bool {
+        !self.consumer.ready()
+    }
+
+    /// applies queued commands to `controller` in order until the queue is empty or a command fails
+    ///
+    /// A command blocked by a time constraint stays at the front of the queue so that it is
+    /// retried on the next drain. A command that fails for any other reason, such as a handler
+    /// veto, would fail the same way again, so it is dropped. Either way the error is returned and
+    /// the remaining commands wait for the next drain.
+    pub fn drain_into(
+        &mut self,
+        controller: &mut TimeConstrainedOnOff<'_>,
+    ) -> Result<(), TransitionError> {
+        while let Some(&command) = self.consumer.peek() {
+            let new_state = match command {
+                Command::On => BangBangState::B,
+                Command::Off => BangBangState::A,
+                Command::Toggle => opposite(controller.state()),
+            };
+
+            if let Err(error) = controller.try_set(new_state) {
+                if !error.is_constrained() {
+                    let _ = self.consumer.dequeue();
+                }
+
+                #[cfg(feature = "log")]
+                debug!(
+                    "{:?} failed with {:?}, {} commands remain queued",
+                    command,
+                    error,
+                    self.len()
+                );
+
+                return Err(error);
+            }
+
+            let _ = self.consumer.dequeue();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+//! read-only view of a controller's state that can be shared with other tasks
+
+use bangbang::prelude::*;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// copyable handle reporting the state of the controller it was split from, see
+/// [`TimeConstrainedOnOff::split`](crate::TimeConstrainedOnOff::split)
+///
+/// The state is published after each successful transition, once the handler has run and the
+/// controller has changed state, so a reader never sees a transition that was vetoed or blocked.
+/// A reader may briefly lag behind a transition that is in progress, and readers observe each
+/// published state in order.
+#[derive(Debug, Clone, Copy)]
+pub struct StateReader<'a> {
+    on: &'a AtomicBool,
+}
+
+impl<'a> StateReader<'a> {
+    pub(crate) fn new(on: &'a AtomicBool) -> Self {
+        Self { on }
+    }
+
+    pub(crate) fn publish(&self, on: bool) {
+        self.on.store(on, Ordering::Release);
+    }
+
+    /// most recently published state of the controller
+    pub fn state(&self) -> BangBangState {
+        if self.is_on() {
+            BangBangState::B
+        } else {
+            BangBangState::A
+        }
+    }
+
+    /// whether the controller was most recently published in the `on` state
+    pub fn is_on(&self) -> bool {
+        self.on.load(Ordering::Acquire)
+    }
+
+    /// whether the controller was most recently published in the `off` state
+    pub fn is_off(&self) -> bool {
+        !self.is_on()
+    }
+}
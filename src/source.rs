@@ -0,0 +1,199 @@
+//! sources of commands that drive a controller through [`TimeConstrainedOnOff::run`], and replay
+//! of recorded commands
+
+use crate::{Command, StepReport, TimeConstrainedOnOff, TransitionError};
+use bangbang::prelude::*;
+use core::convert::TryFrom;
+use core::fmt;
+use core::time::Duration;
+
+/// produces the command, if any, to carry out at each step of a simulation or control loop
+pub trait CommandSource {
+    /// the command to carry out at the provided time, or `None` to leave the state unchanged
+    fn next_command(&mut self, now: u32) -> Option<Command>;
+}
+
+impl<S: CommandSource + ?Sized> CommandSource for &mut S {
+    fn next_command(&mut self, now: u32) -> Option<Command> {
+        (**self).next_command(now)
+    }
+}
+
+/// requests a toggle on the first step and then every `period`, producing a square wave when the
+/// time constraints allow it
+#[derive(Debug, Clone, Copy)]
+pub struct PeriodicToggle {
+    period: u32,
+    next_at: Option<u32>,
+}
+
+impl PeriodicToggle {
+    /// creates a source requesting a toggle every `period`, saturating at the range of the clock
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period: u32::try_from(period.as_millis()).unwrap_or(u32::MAX),
+            next_at: None,
+        }
+    }
+}
+
+impl CommandSource for PeriodicToggle {
+    fn next_command(&mut self, now: u32) -> Option<Command> {
+        let next_at = self.next_at.unwrap_or(now);
+        if now < next_at {
+            return None;
+        }
+
+        // scheduled from the previous toggle rather than from `now`, so the period does not drift
+        self.next_at = Some(next_at.saturating_add(self.period));
+        Some(Command::Toggle)
+    }
+}
+
+/// requests `on` at or below `low` and `off` at or above `high` from a measurement taken at each
+/// step, like [`HysteresisOnOff`](crate::hysteresis::HysteresisOnOff)
+pub struct ThresholdSource<F> {
+    measure: F,
+    low: f32,
+    high: f32,
+}
+
+impl<F> fmt::Debug for ThresholdSource<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ThresholdSource {{ low: {}, high: {} }}",
+            self.low, self.high
+        )
+    }
+}
+
+impl<F: FnMut(u32) -> f32> ThresholdSource<F> {
+    /// creates a source comparing the value returned by `measure` for the time of each step with
+    /// the thresholds
+    pub fn new(measure: F, low: f32, high: f32) -> Self {
+        Self { measure, low, high }
+    }
+}
+
+impl<F: FnMut(u32) -> f32> CommandSource for ThresholdSource<F> {
+    fn next_command(&mut self, now: u32) -> Option<Command> {
+        let measurement = (self.measure)(now);
+
+        if measurement <= self.low {
+            Some(Command::On)
+        } else if measurement >= self.high {
+            Some(Command::Off)
+        } else {
+            None
+        }
+    }
+}
+
+/// iterator returned by [`TimeConstrainedOnOff::run`], yielding the time and outcome of each step
+pub struct Run<'r, 'a, S, C> {
+    controller: &'r mut TimeConstrainedOnOff<'a>,
+    source: S,
+    clock: C,
+    steps: usize,
+}
+
+impl<S, C> fmt::Debug for Run<'_, '_, S, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Run {{ controller: {:?}, steps: {} }}",
+            self.controller, self.steps
+        )
+    }
+}
+
+impl<'r, 'a, S, C> Run<'r, 'a, S, C> {
+    pub(crate) fn new(
+        controller: &'r mut TimeConstrainedOnOff<'a>,
+        source: S,
+        clock: C,
+        steps: usize,
+    ) -> Self {
+        Self {
+            controller,
+            source,
+            clock,
+            steps,
+        }
+    }
+}
+
+impl<S: CommandSource, C: FnMut() -> u32> Iterator for Run<'_, '_, S, C> {
+    type Item = (u32, StepReport);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.steps = self.steps.checked_sub(1)?;
+
+        let now = (self.clock)();
+        // commands the controller already satisfies would restart its timer, so they are dropped
+        let command = match self.source.next_command(now) {
+            Some(Command::On) if self.controller.state() == BangBangState::B => None,
+            Some(Command::Off) if self.controller.state() == BangBangState::A => None,
+            command => command,
+        };
+
+        Some((now, self.controller.step(now, command)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.steps, Some(self.steps))
+    }
+}
+
+/// applies a recorded trace of timestamped commands to a controller, yielding the time, the
+/// resulting state and the outcome of each
+///
+/// Commands are carried out in order with [`TimeConstrainedOnOff::set_at`] or
+/// [`TimeConstrainedOnOff::bang_at`], so the `now` method is not used and a trace captured in the
+/// field replays the same way every time against a freshly created controller.
+pub struct Replay<'r, 'a, 't> {
+    controller: &'r mut TimeConstrainedOnOff<'a>,
+    trace: core::slice::Iter<'t, (u32, Command)>,
+}
+
+impl fmt::Debug for Replay<'_, '_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Replay {{ controller: {:?}, remaining: {} }}",
+            self.controller,
+            self.trace.len()
+        )
+    }
+}
+
+impl<'r, 'a, 't> Replay<'r, 'a, 't> {
+    /// prepares `trace` to be replayed against `controller`, which happens as the replay is
+    /// iterated
+    pub fn new(controller: &'r mut TimeConstrainedOnOff<'a>, trace: &'t [(u32, Command)]) -> Self {
+        Self {
+            controller,
+            trace: trace.iter(),
+        }
+    }
+}
+
+impl Iterator for Replay<'_, '_, '_> {
+    type Item = (u32, BangBangState, Result<(), TransitionError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &(now, command) = self.trace.next()?;
+        let result = match command {
+            Command::On => self.controller.set_at(now, BangBangState::B),
+            Command::Off => self.controller.set_at(now, BangBangState::A),
+            Command::Toggle => self.controller.bang_at(now),
+        };
+
+        Some((now, self.controller.state(), result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.trace.size_hint()
+    }
+}
@@ -0,0 +1,61 @@
+//! boolean health of a controller packed into a register-style byte
+
+use core::ops::BitOr;
+
+/// status word reported by
+/// [`TimeConstrainedOnOff::status_flags`](crate::TimeConstrainedOnOff::status_flags), one bit per
+/// condition
+///
+/// The bit assignments are stable, new conditions only ever take unused bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct StatusFlags(u8);
+
+impl StatusFlags {
+    /// bit 0, the controller is `on`
+    pub const ON: StatusFlags = StatusFlags(1 << 0);
+    /// bit 1, a time constraint currently blocks leaving the current state
+    pub const CONSTRAINED: StatusFlags = StatusFlags(1 << 1);
+    /// bit 2, the most recent transition was vetoed by its handler
+    pub const VETOED: StatusFlags = StatusFlags(1 << 2);
+    /// bit 3, stall detection reports the clock as stalled
+    pub const CLOCK_STALLED: StatusFlags = StatusFlags(1 << 3);
+    /// bit 4, the state has not been set since the controller was created without a known state
+    pub const UNKNOWN: StatusFlags = StatusFlags(1 << 4);
+
+    /// no flags set
+    pub const fn empty() -> Self {
+        StatusFlags(0)
+    }
+
+    /// flags from a raw status byte, keeping unassigned bits as they are
+    pub const fn from_bits(bits: u8) -> Self {
+        StatusFlags(bits)
+    }
+
+    /// the raw status byte
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// whether every flag set in `other` is also set in `self`
+    pub const fn contains(self, other: StatusFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub(crate) fn set_if(self, flag: StatusFlags, condition: bool) -> Self {
+        if condition {
+            self | flag
+        } else {
+            self
+        }
+    }
+}
+
+impl BitOr for StatusFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        StatusFlags(self.0 | other.0)
+    }
+}
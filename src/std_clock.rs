@@ -0,0 +1,13 @@
+//! millisecond clock backed by the standard library, for controllers running on hosted targets
+
+use std::sync::OnceLock;
+use std::time::Instant;
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// milliseconds elapsed on a monotonic clock since this method was first called
+///
+/// The count wraps around after `u32::MAX` milliseconds, roughly 49.7 days.
+pub fn now_ms() -> u32 {
+    START.get_or_init(Instant::now).elapsed().as_millis() as u32
+}
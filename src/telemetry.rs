@@ -0,0 +1,47 @@
+//! counters and gauges for monitoring a controller, and their export to the [`metrics`] crate
+
+#[cfg(feature = "metrics")]
+use crate::TimeConstrainedOnOff;
+
+/// snapshot of the counters and gauges of a controller, returned by
+/// [`TimeConstrainedOnOff::metrics`](crate::TimeConstrainedOnOff::metrics)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    /// successful transitions since creation, including forced transitions and resets
+    pub transitions: u64,
+    /// whether the controller is `on`
+    pub on: bool,
+    /// fraction of the time since creation spent in the `on` state
+    pub duty_cycle: f32,
+    /// transitions blocked by a time constraint, as reported by
+    /// [`TimeConstrainedOnOff::blocked_attempt_count`](crate::TimeConstrainedOnOff::blocked_attempt_count)
+    pub blocked_attempts: u32,
+}
+
+#[cfg(feature = "metrics")]
+impl TimeConstrainedOnOff<'_> {
+    /// records [`TimeConstrainedOnOff::metrics`] with the installed [`metrics`] recorder, labelled
+    /// with `controller` to tell several controllers apart
+    ///
+    /// | Metric | Kind | Value |
+    /// | --- | --- | --- |
+    /// | `bangbang_timed_transitions_total` | counter | [`Metrics::transitions`] |
+    /// | `bangbang_timed_on` | gauge | `1` when `on`, `0` when `off` |
+    /// | `bangbang_timed_duty_cycle` | gauge | [`Metrics::duty_cycle`] |
+    /// | `bangbang_timed_blocked_attempts_total` | counter | [`Metrics::blocked_attempts`] |
+    ///
+    /// Counters are recorded as absolute values, so call this periodically, such as before each
+    /// scrape, rather than after every transition.
+    pub fn record_metrics(&self, controller: &'static str) {
+        let snapshot = self.metrics();
+        let on = if snapshot.on { 1.0 } else { 0.0 };
+
+        metrics::counter!("bangbang_timed_transitions_total", "controller" => controller)
+            .absolute(snapshot.transitions);
+        metrics::gauge!("bangbang_timed_on", "controller" => controller).set(on);
+        metrics::gauge!("bangbang_timed_duty_cycle", "controller" => controller)
+            .set(f64::from(snapshot.duty_cycle));
+        metrics::counter!("bangbang_timed_blocked_attempts_total", "controller" => controller)
+            .absolute(u64::from(snapshot.blocked_attempts));
+    }
+}
@@ -0,0 +1,49 @@
+//! asynchronous waiting for time constraints using a timer supplied once at construction
+
+use crate::{TimeConstrainedOnOff, TransitionError};
+use core::time::Duration;
+
+/// asynchronous delay provided by the executor or hardware abstraction layer in use
+// executors on embedded targets are usually single threaded, so no `Send` bound is imposed
+#[allow(async_fn_in_trait)]
+pub trait Timer {
+    /// completes once `duration` has passed
+    async fn sleep(&self, duration: Duration);
+}
+
+/// time constrained controller paired with the [`Timer`] used to wait out its constraints
+#[derive(Debug)]
+pub struct AsyncOnOff<'a, T> {
+    controller: TimeConstrainedOnOff<'a>,
+    timer: T,
+}
+
+impl<'a, T: Timer> AsyncOnOff<'a, T> {
+    /// pairs a controller with the timer used to wait out its constraints
+    pub fn new(controller: TimeConstrainedOnOff<'a>, timer: T) -> Self {
+        Self { controller, timer }
+    }
+
+    /// waits until the minimum time constraint of the current state is satisfied, then
+    /// transitions to the opposite state
+    ///
+    /// The remaining time is checked again after every sleep, so a timer that wakes early only
+    /// costs another sleep. The handler may still veto the transition.
+    pub async fn bang_when_ready(&mut self) -> Result<(), TransitionError> {
+        while let Some(remaining) = self.controller.time_remaining() {
+            self.timer.sleep(remaining).await;
+        }
+
+        self.controller.try_bang()
+    }
+
+    /// the wrapped time constrained controller
+    pub fn controller(&self) -> &TimeConstrainedOnOff<'a> {
+        &self.controller
+    }
+
+    /// mutable access to the wrapped time constrained controller
+    pub fn controller_mut(&mut self) -> &mut TimeConstrainedOnOff<'a> {
+        &mut self.controller
+    }
+}
@@ -154,3 +154,566 @@ fn calls_handlers() {
         assert_eq!(*called_off_handler, true);
     }
 }
+
+#[test]
+fn reset_to_fires_handler_on_request() {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    let called_on_handler = Arc::new(Mutex::new(0));
+    let called_on_inner_handler = Arc::clone(&called_on_handler);
+    let mut handle_on = move || {
+        *called_on_inner_handler.lock().unwrap() += 1;
+        Ok(())
+    };
+
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, Some(&mut handle_on), None, None, None, &now);
+
+    assert!(on_off.reset_to(BangBangState::B, false).is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(*called_on_handler.lock().unwrap(), 0);
+
+    assert!(on_off.reset_to(BangBangState::A, false).is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    assert!(on_off.reset_to(BangBangState::B, true).is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(*called_on_handler.lock().unwrap(), 1);
+}
+
+#[test]
+fn reset_to_respects_handler_veto() {
+    let mut handle_on = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::A,
+            to: BangBangState::B,
+            code: 42,
+        })
+    };
+
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, Some(&mut handle_on), None, None, None, &now);
+
+    assert!(on_off.reset_to(BangBangState::B, true).is_err());
+    assert_eq!(on_off.is_off(), true);
+
+    assert!(on_off.reset_to(BangBangState::B, false).is_ok());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn default_toggles_freely() {
+    let mut on_off = TimeConstrainedOnOff::default();
+
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(on_off.minimum_on(), None);
+    assert_eq!(on_off.minimum_off(), None);
+
+    for _ in 0..10 {
+        assert!(on_off.bang().is_ok());
+    }
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn reads_clock_once_per_transition() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let reads = Arc::new(AtomicUsize::new(0));
+    let reads_inner = Arc::clone(&reads);
+    let now = move || {
+        let _ = reads_inner.fetch_add(1, Ordering::SeqCst);
+        0
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(reads.load(Ordering::SeqCst), 2);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(reads.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn reports_installed_handlers() {
+    let mut handle_on = || Ok(());
+    let now = || 0;
+
+    let on_off = TimeConstrainedOnOff::new(false, Some(&mut handle_on), None, None, None, &now);
+
+    assert_eq!(on_off.has_on_handler(), true);
+    assert_eq!(on_off.has_off_handler(), false);
+}
+
+#[test]
+fn maps_state_to_values() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    assert_eq!(on_off.mapped(255u8, 7u8), 255);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.mapped(255u8, 7u8), 7);
+}
+
+#[test]
+// the state mirror is interior mutable but is not part of the hash
+#[allow(clippy::mutable_key_type)]
+fn equal_configurations_collide_in_hash_set() {
+    use core::time::Duration;
+    use std::collections::HashSet;
+
+    let now = || 0;
+    let mut handle_on = || Ok(());
+    let minimum = Some(Duration::from_millis(10));
+
+    let mut controllers = HashSet::new();
+    assert!(controllers.insert(TimeConstrainedOnOff::new(
+        false, None, None, minimum, None, &now
+    )));
+    // handlers are not part of the identity, so this is considered the same controller
+    assert!(!controllers.insert(TimeConstrainedOnOff::new(
+        false,
+        Some(&mut handle_on),
+        None,
+        minimum,
+        None,
+        &now,
+    )));
+    assert!(controllers.insert(TimeConstrainedOnOff::new(
+        true, None, None, minimum, None, &now
+    )));
+    assert!(controllers.insert(TimeConstrainedOnOff::new(
+        false, None, None, None, None, &now
+    )));
+
+    assert_eq!(controllers.len(), 3);
+}
+
+#[test]
+fn without_handlers_matches_new() {
+    use core::time::Duration;
+
+    let now = || 0;
+    let minimum = Some(Duration::from_millis(10));
+
+    let mut short = TimeConstrainedOnOff::without_handlers(true, minimum, None, &now);
+    let mut full = TimeConstrainedOnOff::new(true, None, None, minimum, None, &now);
+
+    assert_eq!(short, full);
+    assert_eq!(short.has_on_handler(), false);
+    assert_eq!(short.has_off_handler(), false);
+
+    for &at in &[9, 10, 10, 11] {
+        assert_eq!(short.bang_at(at).is_ok(), full.bang_at(at).is_ok());
+        assert_eq!(short.state(), full.state());
+    }
+    assert_eq!(short.is_on(), true);
+}
+
+#[test]
+fn measures_handler_duration() {
+    use std::sync::{Arc, Mutex};
+
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    // the slow handler advances the clock while it runs
+    let faux_clock_on = Arc::clone(&faux_clock);
+    let mut handle_on = move || {
+        *faux_clock_on.lock().unwrap() += 7;
+        Ok(())
+    };
+    let mut handle_off = || Ok(());
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        Some(&mut handle_on),
+        Some(&mut handle_off),
+        None,
+        None,
+        &now,
+    )
+    .with_handler_timing();
+
+    assert_eq!(on_off.last_on_handler_duration(), None);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(
+        on_off.last_on_handler_duration(),
+        Some(core::time::Duration::from_millis(7))
+    );
+    assert_eq!(on_off.last_off_handler_duration(), None);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(
+        on_off.last_off_handler_duration(),
+        Some(core::time::Duration::from_millis(0))
+    );
+}
+
+#[test]
+fn unified_handler_receives_target_state() {
+    use std::sync::{Arc, Mutex};
+
+    let targets = Arc::new(Mutex::new(Vec::new()));
+    let targets_inner = Arc::clone(&targets);
+    let mut handler = move |state: BangBangState| {
+        targets_inner.lock().unwrap().push(state);
+        if targets_inner.lock().unwrap().len() > 2 {
+            Err(BangBangError::StateChangeTemporarilyConstrained {
+                from: BangBangState::B,
+                to: state,
+                code: 7,
+            })
+        } else {
+            Ok(())
+        }
+    };
+    let now = || 0;
+
+    let mut on_off =
+        TimeConstrainedOnOff::with_unified_handler(true, &mut handler, None, None, &now);
+    assert_eq!(on_off.has_on_handler(), true);
+    assert_eq!(on_off.has_off_handler(), true);
+
+    assert!(on_off.bang().is_ok());
+    assert!(on_off.bang().is_ok());
+    // the third call vetoes the transition
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    assert_eq!(
+        *targets.lock().unwrap(),
+        vec![BangBangState::A, BangBangState::B, BangBangState::A]
+    );
+}
+
+#[test]
+fn advisory_handler_does_not_block() {
+    let now = || 0;
+    let mut handle_off = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::B,
+            to: BangBangState::A,
+            code: 7,
+        })
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, Some(&mut handle_off), None, None, &now)
+        .with_advisory_handlers();
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+    match on_off.last_error() {
+        Some(TransitionError::Vetoed(BangBangError::StateChangeTemporarilyConstrained {
+            code,
+            ..
+        })) => assert_eq!(*code, 7),
+        _ => panic!("expected the handler error to be recorded"),
+    }
+
+    assert!(on_off.bang().is_ok());
+    assert!(on_off.last_error().is_none());
+}
+
+#[test]
+fn swaps_handlers_at_runtime() {
+    use core::time::Duration;
+    use std::sync::{Arc, Mutex};
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let calls_normal = Arc::clone(&calls);
+    let mut normal = move || {
+        calls_normal.lock().unwrap().push("normal");
+        Ok(())
+    };
+    let calls_test = Arc::clone(&calls);
+    let mut test_mode = move || {
+        calls_test.lock().unwrap().push("test");
+        Ok(())
+    };
+    let now = || 0;
+
+    let minimum = Some(Duration::from_millis(10));
+    let mut on_off =
+        TimeConstrainedOnOff::new(false, Some(&mut normal), None, minimum, minimum, &now);
+
+    assert!(on_off.bang_at(10).is_ok());
+    assert!(on_off.bang_at(20).is_ok());
+
+    on_off.set_on_handler(Some(&mut test_mode));
+    // the off period that started at 20 is still being timed
+    assert!(on_off.bang_at(29).is_err());
+    assert!(on_off.bang_at(30).is_ok());
+    assert!(on_off.bang_at(40).is_ok());
+
+    on_off.set_on_handler(None);
+    assert_eq!(on_off.has_on_handler(), false);
+    assert!(on_off.bang_at(50).is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    assert_eq!(*calls.lock().unwrap(), vec!["normal", "test"]);
+}
+
+#[test]
+fn packs_status_round_trip() {
+    use core::time::Duration;
+
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(1_234)),
+        Some(Duration::from_secs(30 * 24 * 60 * 60)),
+        &now,
+    );
+    assert_eq!(unpack_status(on_off.pack_status()), (true, 1_234));
+
+    on_off.set_minimum_on(None);
+    assert_eq!(on_off.pack_status(), 1 << 31);
+    assert_eq!(unpack_status(on_off.pack_status()), (true, 0));
+
+    // thirty days does not fit in 31 bits of milliseconds
+    assert!(on_off.bang().is_ok());
+    assert_eq!(unpack_status(on_off.pack_status()), (false, 0x7FFF_FFFF));
+
+    on_off.set_minimum_off(Some(Duration::from_millis(1)));
+    assert_eq!(on_off.pack_status(), 1);
+}
+
+#[test]
+fn try_from_validates_tuple() {
+    use core::convert::TryFrom;
+    use core::time::Duration;
+
+    // the tuple is not coerced, so the clock is passed as a trait object explicitly
+    let now = || 0;
+    let now: &(dyn Fn() -> u32 + Sync) = &now;
+
+    let on_off =
+        TimeConstrainedOnOff::try_from((true, Some(Duration::from_millis(10)), None, now)).unwrap();
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(10)));
+
+    let over_range = Duration::from_millis(u64::from(u32::MAX) + 1);
+    assert_eq!(
+        TimeConstrainedOnOff::try_from((false, None, Some(over_range), now)).unwrap_err(),
+        ConfigError::ExceedsClockRange(BangBangState::A)
+    );
+}
+
+#[test]
+fn new_deferred_does_not_read_clock() {
+    use core::time::Duration;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let reads = Arc::new(AtomicUsize::new(0));
+    let reads_inner = Arc::clone(&reads);
+    let now = move || {
+        let _ = reads_inner.fetch_add(1, Ordering::SeqCst);
+        100
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new_deferred(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    assert_eq!(reads.load(Ordering::SeqCst), 0);
+
+    // the timer has not started, so the minimum on time does not apply yet
+    assert_eq!(on_off.time_remaining(), None);
+    assert!(on_off.bang_at(0).is_ok());
+    assert!(on_off.bang_at(5).is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert!(on_off.bang_at(14).is_err());
+
+    on_off.start(20);
+    assert!(on_off.bang_at(29).is_err());
+    assert!(on_off.bang_at(30).is_ok());
+}
+
+#[test]
+fn unknown_state_until_first_set() {
+    use core::time::Duration;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let now = || 0;
+    let offs = Arc::new(AtomicUsize::new(0));
+    let offs_inner = Arc::clone(&offs);
+    let mut handle_off = move || {
+        let _ = offs_inner.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new_unknown(
+        None,
+        Some(&mut handle_off),
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(10)),
+        &now,
+    );
+    assert_eq!(on_off.tristate(), Tristate::Unknown);
+    assert_eq!(on_off.is_on(), false);
+    assert_eq!(on_off.is_off(), false);
+
+    // establishing the placeholder state still commands it and needs no minimum
+    assert!(on_off.set_at(3, BangBangState::A).is_ok());
+    assert_eq!(on_off.tristate(), Tristate::Off);
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(offs.load(Ordering::SeqCst), 1);
+
+    // from then on the minimums apply as usual
+    assert!(on_off.set_at(12, BangBangState::B).is_err());
+    assert!(on_off.set_at(13, BangBangState::B).is_ok());
+    assert_eq!(on_off.tristate(), Tristate::On);
+}
+
+#[test]
+fn status_flags_report_conditions() {
+    use core::time::Duration;
+
+    let now = || 0;
+    let mut handle_off = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::B,
+            to: BangBangState::A,
+            code: 7,
+        })
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        Some(&mut handle_off),
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    let flags = on_off.status_flags();
+    assert_eq!(flags, StatusFlags::ON | StatusFlags::CONSTRAINED);
+    assert_eq!(flags.bits(), 0b0000_0011);
+
+    on_off.set_minimum_on(None);
+    assert!(on_off.bang().is_err());
+    let flags = on_off.status_flags();
+    assert_eq!(flags.contains(StatusFlags::VETOED), true);
+    assert_eq!(flags.contains(StatusFlags::CONSTRAINED), false);
+    assert_eq!(flags.bits(), 0b0000_0101);
+}
+
+#[test]
+fn handler_chain_stops_at_veto_in_the_middle() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    type Handler = dyn FnMut() -> Result<(), BangBangError> + Sync + Send;
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    let now = || 0;
+    let first = Box::leak(Box::new(|| {
+        let _ = CALLS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }));
+    let second = Box::leak(Box::new(|| {
+        let _ = CALLS.fetch_add(10, Ordering::SeqCst);
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::A,
+            to: BangBangState::B,
+            code: 7,
+        })
+    }));
+    let third = Box::leak(Box::new(|| {
+        let _ = CALLS.fetch_add(100, Ordering::SeqCst);
+        Ok(())
+    }));
+    let handlers: &'static mut [&'static mut Handler] = Box::leak(Box::new([first, second, third]));
+
+    let mut chain = HandlerChain::new(handlers);
+    assert_eq!(chain.len(), 3);
+    let mut handle_on = move || chain.call();
+
+    let mut on_off = TimeConstrainedOnOff::new(false, Some(&mut handle_on), None, None, None, &now);
+
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(CALLS.load(Ordering::SeqCst), 11);
+}
+
+#[test]
+fn has_transitioned_only_after_success() {
+    use core::time::Duration;
+
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    assert_eq!(on_off.has_transitioned(), false);
+
+    assert!(on_off.bang_at(5).is_err());
+    assert_eq!(on_off.has_transitioned(), false);
+
+    assert!(on_off.bang_at(10).is_ok());
+    assert_eq!(on_off.has_transitioned(), true);
+}
+
+#[test]
+fn reads_through_shared_reference() {
+    use core::time::Duration;
+
+    fn render(on_off: &TimeConstrainedOnOff<'_>) -> (bool, bool, BangBangState, bool, Duration) {
+        (
+            on_off.is_on(),
+            on_off.is_off(),
+            on_off.state(),
+            on_off.time_remaining().is_some(),
+            on_off.time_in_state(),
+        )
+    }
+
+    let now = || 4;
+    let on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    let shared = &on_off;
+
+    let expected = (
+        true,
+        false,
+        BangBangState::B,
+        true,
+        Duration::from_millis(0),
+    );
+    assert_eq!(render(shared), expected);
+    assert_eq!(render(shared), expected);
+}
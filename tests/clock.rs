@@ -0,0 +1,208 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+
+fn controller_before_wrap<'a>(
+    now: &'a (dyn Fn() -> u32 + Sync + 'static),
+    policy: ClockPolicy,
+) -> TimeConstrainedOnOff<'a> {
+    TimeConstrainedOnOff::new(true, None, None, Some(Duration::from_millis(10)), None, now)
+        .with_clock_policy(policy)
+}
+
+#[test]
+fn restarting_clock_counts_from_zero() {
+    let now = || u32::MAX - 5;
+
+    let mut on_off = controller_before_wrap(&now, ClockPolicy::default());
+
+    assert!(on_off.bang_at(9).is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    assert!(on_off.bang_at(10).is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn wrapping_clock_counts_across_wrap() {
+    let now = || u32::MAX - 5;
+
+    let mut on_off = controller_before_wrap(&now, ClockPolicy::Wrapping);
+
+    assert!(on_off.bang_at(3).is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    assert!(on_off.bang_at(4).is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn saturating_clock_blocks_backwards_jump() {
+    let now = || u32::MAX - 5;
+
+    let mut on_off = controller_before_wrap(&now, ClockPolicy::Saturating);
+
+    assert!(on_off.bang_at(4).is_err());
+    assert!(on_off.bang_at(1_000).is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    assert!(on_off.bang_at(u32::MAX).is_err());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn monotonic_clock_blocks_backwards_jump() {
+    let now = || 100;
+
+    let mut on_off = controller_before_wrap(&now, ClockPolicy::Monotonic);
+
+    assert!(on_off.bang_at(50).is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    assert!(on_off.bang_at(110).is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn dual_clocks_measure_each_state_independently() {
+    use std::sync::{Arc, Mutex};
+
+    let on_clock = Arc::new(Mutex::new(0u32));
+    let off_clock = Arc::new(Mutex::new(0u32));
+    let on_clock_inner = on_clock.clone();
+    let off_clock_inner = off_clock.clone();
+    let read_on_clock = move || on_clock_inner.lock().unwrap().clone();
+    let read_off_clock = move || off_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(20)),
+        &read_on_clock,
+    )
+    .with_dual_clock(&read_on_clock, &read_off_clock);
+
+    // only the on clock counts while on
+    *off_clock.lock().unwrap() += 100;
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    *on_clock.lock().unwrap() += 10;
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    // only the off clock counts while off
+    *on_clock.lock().unwrap() += 100;
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_off(), true);
+
+    *off_clock.lock().unwrap() += 19;
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(1)));
+
+    *off_clock.lock().unwrap() += 1;
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn detects_stalled_clock() {
+    use std::sync::{Arc, Mutex};
+
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_stall_detection(3);
+
+    assert!(on_off.bang().is_err());
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.clock_stalled(), false);
+
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.clock_stalled(), true);
+
+    // a single advance of the clock clears the stall
+    *faux_clock.lock().unwrap() = 1;
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.clock_stalled(), false);
+}
+
+#[test]
+fn stall_detection_is_off_by_default() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    for _ in 0..100 {
+        assert!(on_off.bang().is_err());
+    }
+    assert_eq!(on_off.clock_stalled(), false);
+}
+
+#[test]
+fn elapsed_since_counts_across_wrap() {
+    let now = || 4;
+
+    let on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    assert_eq!(on_off.elapsed_since(0), Duration::from_millis(4));
+    assert_eq!(on_off.elapsed_since(4), Duration::from_millis(0));
+    assert_eq!(
+        on_off.elapsed_since(u32::MAX - 5),
+        Duration::from_millis(10)
+    );
+}
+
+#[test]
+fn reports_time_unit() {
+    assert_eq!(bangbang_timed::TIME_UNIT, "milliseconds");
+    assert_eq!(TimeConstrainedOnOff::time_unit(), "milliseconds");
+}
+
+#[test]
+fn counts_backward_clock_jumps() {
+    let now = || 100;
+
+    let mut on_off = controller_before_wrap(&now, ClockPolicy::Monotonic);
+    assert_eq!(on_off.backward_jump_count(), 0);
+
+    assert!(on_off.bang_at(50).is_err());
+    assert!(on_off.bang_at(60).is_err());
+    assert!(on_off.poll_at(70).unwrap().is_none());
+    // the clock went backwards once and has been moving forward since
+    assert_eq!(on_off.backward_jump_count(), 1);
+
+    assert!(on_off.bang_at(110).is_ok());
+    assert!(on_off.bang_at(120).is_ok());
+    assert_eq!(on_off.backward_jump_count(), 1);
+
+    assert!(on_off.bang_at(10).is_err());
+    assert_eq!(on_off.backward_jump_count(), 2);
+}
+
+#[test]
+fn wrapping_clock_is_not_a_backward_jump() {
+    let now = || u32::MAX - 5;
+
+    let mut on_off = controller_before_wrap(&now, ClockPolicy::Wrapping);
+
+    assert!(on_off.bang_at(3).is_err());
+    assert!(on_off.bang_at(4).is_ok());
+    assert_eq!(on_off.backward_jump_count(), 0);
+}
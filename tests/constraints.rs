@@ -315,3 +315,1525 @@ fn calls_handlers_after_constraint_met() {
         assert_eq!(*called_off_handler, false);
     }
 }
+
+#[test]
+fn simulate_bang_predicts_bang_at() {
+    let now = || 0;
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(faux_ten_milliseconds),
+        Some(faux_ten_milliseconds),
+        &now,
+    );
+
+    for &at in &[0, 5, 9, 10, 12, 21, 22, 40] {
+        let predicted = on_off.simulate_bang(at);
+        let state = on_off.state();
+        let result = on_off.bang_at(at);
+
+        match predicted {
+            SimResult::WouldTransition(to) => {
+                assert!(result.is_ok());
+                assert_eq!(on_off.state(), to);
+            }
+            SimResult::BlockedByConstraint { remaining } => {
+                assert!(result.is_err());
+                assert_eq!(on_off.state(), state);
+                assert!(remaining > Duration::from_millis(0));
+            }
+            SimResult::LikelyVetoed(_) => panic!("no veto predicate is installed"),
+        }
+    }
+
+    assert_eq!(
+        on_off.simulate_bang(45),
+        SimResult::BlockedByConstraint {
+            remaining: Duration::from_millis(5)
+        }
+    );
+    assert_eq!(
+        on_off.simulate_bang(50),
+        SimResult::WouldTransition(BangBangState::B)
+    );
+}
+
+#[test]
+fn fallback_decides_constrained_transitions() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let consulted = Arc::new(Mutex::new(0));
+    let consulted_inner = Arc::clone(&consulted);
+    let mut force = move |_: &TimeConstrainedOnOff<'_>| {
+        *consulted_inner.lock().unwrap() += 1;
+        Decision::Force
+    };
+    let mut abort = |_: &TimeConstrainedOnOff<'_>| Decision::Abort;
+
+    {
+        let mut on_off =
+            TimeConstrainedOnOff::new(true, None, None, Some(faux_ten_milliseconds), None, &now)
+                .with_fallback(&mut force);
+
+        assert!(on_off.bang().is_ok());
+        assert_eq!(on_off.is_off(), true);
+        assert_eq!(*consulted.lock().unwrap(), 1);
+
+        // no constraint on the `off` state, so the fallback is not consulted
+        assert!(on_off.bang().is_ok());
+        assert_eq!(on_off.is_on(), true);
+        assert_eq!(*consulted.lock().unwrap(), 1);
+    }
+
+    {
+        let mut on_off =
+            TimeConstrainedOnOff::new(true, None, None, Some(faux_ten_milliseconds), None, &now)
+                .with_fallback(&mut abort);
+
+        match on_off.bang() {
+            Err(BangBangError::StateChangeTemporarilyConstrained { code, .. }) => {
+                assert_eq!(code, 1)
+            }
+            _ => panic!("expected the transition to be aborted"),
+        }
+        assert_eq!(on_off.is_on(), true);
+
+        *faux_clock.lock().unwrap() = now() + 10;
+
+        assert!(on_off.bang().is_ok());
+        assert_eq!(on_off.is_off(), true);
+    }
+}
+
+#[test]
+fn soonest_allowed_across_controllers() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let ready = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    let slow = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(30)),
+        None,
+        &now,
+    );
+    let fast = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        None,
+        Some(Duration::from_millis(20)),
+        &now,
+    );
+
+    assert_eq!(soonest_allowed(&[&ready]), None);
+    assert_eq!(
+        soonest_allowed(&[&ready, &slow, &fast]),
+        Some(Duration::from_millis(20))
+    );
+
+    *faux_clock.lock().unwrap() = now() + 5;
+
+    assert_eq!(
+        soonest_allowed(&[&slow, &fast, &ready]),
+        Some(Duration::from_millis(15))
+    );
+
+    *faux_clock.lock().unwrap() = now() + 15;
+
+    assert_eq!(
+        soonest_allowed(&[&slow, &fast, &ready]),
+        Some(Duration::from_millis(10))
+    );
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    assert_eq!(soonest_allowed(&[&slow, &fast, &ready]), None);
+}
+
+#[test]
+fn unsatisfiable_constraints_are_clamped() {
+    let now = || 0;
+    let sixty_days = Duration::from_secs(60 * 24 * 60 * 60);
+    let longest = Duration::from_millis(u64::from(u32::MAX));
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, Some(sixty_days), &now);
+    assert_eq!(on_off.minimum_off(), Some(longest));
+
+    on_off.set_minimum_on(Some(sixty_days));
+    assert_eq!(on_off.minimum_on(), Some(longest));
+
+    on_off.set_maximum_off(Some(sixty_days));
+    assert_eq!(on_off.maximum_off(), Some(longest));
+
+    // the clamped minimum can be satisfied by the longest span the clock can measure
+    assert!(on_off.bang_at(u32::MAX - 1).is_err());
+    assert!(on_off.bang_at(u32::MAX).is_ok());
+}
+
+#[test]
+fn from_onoff_preserves_state_and_constrains() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let called_on_handler = Arc::new(Mutex::new(false));
+    let called_on_inner_handler = Arc::clone(&called_on_handler);
+    let mut handle_on = move || {
+        *called_on_inner_handler.lock().unwrap() = true;
+        Ok(())
+    };
+
+    let mut plain = OnOff::new(true, Some(&mut handle_on), None);
+    assert!(plain.bang().is_ok());
+    assert_eq!(plain.is_off(), true);
+
+    let mut on_off =
+        TimeConstrainedOnOff::from_onoff(plain, None, Some(faux_ten_milliseconds), &now);
+
+    assert_eq!(on_off.is_on(), false);
+    assert_eq!(on_off.is_off(), true);
+
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(*called_on_handler.lock().unwrap(), false);
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(*called_on_handler.lock().unwrap(), true);
+}
+
+#[test]
+fn min_before_on_is_minimum_off() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+
+    on_off.set_min_before_on(Some(faux_ten_milliseconds));
+    assert_eq!(on_off.min_before_on(), Some(faux_ten_milliseconds));
+    assert_eq!(on_off.minimum_off(), Some(faux_ten_milliseconds));
+    assert_eq!(on_off.min_before_off(), None);
+    assert_eq!(on_off.minimum_on(), None);
+
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_off(), true);
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    // turning off again is unconstrained
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    on_off.set_min_before_off(Some(faux_ten_milliseconds));
+    assert_eq!(on_off.minimum_on(), Some(faux_ten_milliseconds));
+}
+
+#[test]
+fn reports_time_since_last_blocked() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, None, None, Some(faux_ten_milliseconds), None, &now);
+
+    assert_eq!(on_off.since_last_blocked(), None);
+
+    *faux_clock.lock().unwrap() = now() + 2;
+
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.since_last_blocked(), Some(Duration::from_millis(0)));
+
+    *faux_clock.lock().unwrap() = now() + 3;
+
+    assert_eq!(on_off.since_last_blocked(), Some(Duration::from_millis(3)));
+
+    *faux_clock.lock().unwrap() = now() + 5;
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.since_last_blocked(), None);
+}
+
+#[test]
+fn poll_ready_is_edge_triggered() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(faux_ten_milliseconds),
+        Some(faux_ten_milliseconds),
+        &now,
+    );
+
+    assert_eq!(on_off.poll_ready(), None);
+
+    *faux_clock.lock().unwrap() = now() + 9;
+
+    assert_eq!(on_off.poll_ready(), None);
+
+    *faux_clock.lock().unwrap() = now() + 1;
+
+    assert_eq!(on_off.poll_ready(), Some(BangBangState::A));
+    assert_eq!(on_off.poll_ready(), None);
+
+    *faux_clock.lock().unwrap() = now() + 5;
+
+    assert_eq!(on_off.poll_ready(), None);
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.poll_ready(), None);
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    assert_eq!(on_off.poll_ready(), Some(BangBangState::B));
+    assert_eq!(on_off.poll_ready(), None);
+}
+
+#[test]
+fn suspends_and_resumes_constraints() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(faux_ten_milliseconds),
+        Some(faux_ten_milliseconds),
+        &now,
+    );
+
+    assert_eq!(on_off.constraints_active(), true);
+    assert!(on_off.bang().is_err());
+
+    on_off.suspend_constraints();
+    assert_eq!(on_off.constraints_active(), false);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    on_off.resume_constraints();
+    assert_eq!(on_off.constraints_active(), true);
+    assert_eq!(on_off.minimum_on(), Some(faux_ten_milliseconds));
+    assert_eq!(on_off.minimum_off(), Some(faux_ten_milliseconds));
+
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_off(), true);
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn proportional_off_extends_cooldown() {
+    let now = || 0;
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, None, None, None, Some(faux_ten_milliseconds), &now)
+            .with_proportional_off(2.0);
+
+    // a short on period leaves the configured minimum in charge
+    assert!(on_off.bang_at(2).is_ok());
+    assert!(on_off.bang_at(11).is_err());
+    assert!(on_off.bang_at(12).is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    // a long on period of 50ms requires 100ms off
+    assert!(on_off.bang_at(62).is_ok());
+    assert_eq!(on_off.is_off(), true);
+    assert!(on_off.bang_at(72).is_err());
+    assert!(on_off.bang_at(161).is_err());
+    assert_eq!(on_off.is_off(), true);
+    assert!(on_off.bang_at(162).is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    // the extension is one-shot, a short on period goes back to the configured minimum
+    assert!(on_off.bang_at(163).is_ok());
+    assert!(on_off.bang_at(172).is_err());
+    assert!(on_off.bang_at(173).is_ok());
+}
+
+#[test]
+fn error_carries_remaining_time() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, None, None, Some(faux_ten_milliseconds), None, &now);
+
+    *faux_clock.lock().unwrap() = now() + 3;
+
+    match on_off.try_bang() {
+        Err(error) => assert_eq!(error.remaining(), Some(Duration::from_millis(7))),
+        Ok(()) => panic!("expected the transition to be constrained"),
+    }
+
+    match on_off.bang_at(9) {
+        Err(TransitionError::Constrained { remaining, .. }) => {
+            assert_eq!(remaining, Duration::from_millis(1))
+        }
+        _ => panic!("expected the transition to be constrained"),
+    }
+
+    *faux_clock.lock().unwrap() = now() + 7;
+
+    assert!(on_off.try_bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn prime_presatisfies_minimum() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(faux_ten_milliseconds),
+        Some(faux_ten_milliseconds),
+        &now,
+    );
+
+    on_off.prime(Duration::from_millis(4));
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(6)));
+
+    on_off.prime(Duration::from_millis(20));
+    assert_eq!(on_off.time_remaining(), None);
+
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    // priming only applies to the state that was current at the time
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn bang_n_counts_permitted_transitions() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        &now,
+    );
+
+    // unconstrained in the on state, then blocked in the off state for the rest of the batch
+    assert_eq!(on_off.bang_n(5), 1);
+    assert_eq!(on_off.is_off(), true);
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    // off to on, on to off, then blocked again
+    assert_eq!(on_off.bang_n(5), 2);
+    assert_eq!(on_off.is_off(), true);
+
+    assert_eq!(on_off.bang_n(0), 0);
+}
+
+#[test]
+fn remaining_pairs_both_directions() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let reads = Arc::new(Mutex::new(0 as u32));
+    let reads_inner = Arc::clone(&reads);
+    let now = move || {
+        *reads_inner.lock().unwrap() += 1;
+        faux_clock_inner.lock().unwrap().clone()
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(20)),
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 4;
+    *reads.lock().unwrap() = 0;
+    assert_eq!(on_off.remaining(), (None, Some(Duration::from_millis(6))));
+    assert_eq!(*reads.lock().unwrap(), 1);
+
+    *faux_clock.lock().unwrap() = 10;
+    assert!(on_off.bang().is_ok());
+    *faux_clock.lock().unwrap() = 15;
+    assert_eq!(on_off.remaining(), (Some(Duration::from_millis(15)), None));
+
+    *faux_clock.lock().unwrap() = 30;
+    assert_eq!(on_off.remaining(), (None, None));
+}
+
+#[test]
+fn distinguishes_constrained_from_vetoed() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut handle_off = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::B,
+            to: BangBangState::A,
+            code: 7,
+        })
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        Some(&mut handle_off),
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    match on_off.try_set(BangBangState::A) {
+        Err(error) => {
+            assert_eq!(error.is_constrained(), true);
+            assert_eq!(error.is_vetoed(), false);
+        }
+        Ok(()) => panic!("expected the transition to be constrained"),
+    }
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    match on_off.try_set(BangBangState::A) {
+        Err(TransitionError::Vetoed(BangBangError::StateChangeTemporarilyConstrained {
+            code,
+            ..
+        })) => assert_eq!(code, 7),
+        _ => panic!("expected the transition to be vetoed"),
+    }
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn vetoed_transition_does_not_restart_constraint() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let veto = Arc::new(Mutex::new(true));
+    let veto_inner = Arc::clone(&veto);
+    let mut handle_off = move || {
+        if *veto_inner.lock().unwrap() {
+            Err(BangBangError::StateChangeTemporarilyConstrained {
+                from: BangBangState::B,
+                to: BangBangState::A,
+                code: 7,
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        Some(&mut handle_off),
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 10;
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    // the failed attempt left the on period running since time 0
+    *faux_clock.lock().unwrap() = 15;
+    assert_eq!(on_off.time_remaining(), None);
+    assert_eq!(on_off.transitions_within(Duration::from_millis(100)), 0);
+
+    *veto.lock().unwrap() = false;
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn apply_config_rebases_window() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 8;
+    on_off.apply_config(
+        Some(Duration::from_millis(5)),
+        Some(Duration::from_millis(20)),
+        true,
+    );
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(5)));
+    assert_eq!(on_off.minimum_off(), Some(Duration::from_millis(20)));
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(5)));
+
+    *faux_clock.lock().unwrap() = 12;
+    assert!(on_off.bang().is_err());
+
+    *faux_clock.lock().unwrap() = 13;
+    assert!(on_off.bang().is_ok());
+
+    // without rebasing the window keeps running from the last transition
+    *faux_clock.lock().unwrap() = 20;
+    on_off.apply_config(None, Some(Duration::from_millis(7)), false);
+    assert_eq!(on_off.time_remaining(), None);
+}
+
+#[test]
+fn apply_config_rebases_gated_window() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_gated_time();
+
+    // active the whole time, so active time keeps pace with the clock
+    *faux_clock.lock().unwrap() = 8;
+    on_off.add_active_time(Duration::from_millis(8));
+    on_off.apply_config(Some(Duration::from_millis(5)), None, true);
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(5)));
+
+    *faux_clock.lock().unwrap() = 12;
+    on_off.add_active_time(Duration::from_millis(4));
+    assert!(on_off.bang().is_err());
+
+    *faux_clock.lock().unwrap() = 13;
+    on_off.add_active_time(Duration::from_millis(1));
+    assert!(on_off.bang().is_ok());
+}
+
+#[test]
+fn counts_blocked_attempts() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    assert!(on_off.bang().is_err());
+    assert!(on_off.bang().is_err());
+    assert!(on_off.set(BangBangState::A).is_err());
+    assert_eq!(on_off.blocked_attempt_count(), 3);
+
+    // asking for the current state is not a blocked attempt, nor does it extend the streak
+    *faux_clock.lock().unwrap() = 5;
+    assert!(on_off.set(BangBangState::B).is_ok());
+    assert_eq!(on_off.blocked_attempt_count(), 3);
+    assert_eq!(on_off.since_last_blocked(), Some(Duration::from_millis(5)));
+
+    *faux_clock.lock().unwrap() = 10;
+    assert!(on_off.bang().is_ok());
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.blocked_attempt_count(), 3);
+
+    on_off.reset_blocked_attempt_count();
+    assert_eq!(on_off.blocked_attempt_count(), 0);
+}
+
+#[test]
+fn setting_current_state_is_a_no_op() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let on_calls = Arc::new(Mutex::new(0));
+    let on_calls_inner = Arc::clone(&on_calls);
+    let mut handle_on = move || {
+        *on_calls_inner.lock().unwrap() += 1;
+        Ok(())
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        Some(&mut handle_on),
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 6;
+    assert!(on_off.set(BangBangState::B).is_ok());
+    assert!(on_off.apply(Command::On).is_ok());
+    assert_eq!(*on_calls.lock().unwrap(), 0);
+    assert_eq!(on_off.has_transitioned(), false);
+    assert_eq!(on_off.metrics().transitions, 0);
+
+    // the minimum still runs from creation, not from the no-op requests
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(4)));
+}
+
+#[test]
+fn new_ms_enforces_exact_boundaries() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new_ms(true, None, None, Some(10), Some(u32::MAX), &now);
+
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(10)));
+
+    *faux_clock.lock().unwrap() = 9;
+    assert!(on_off.bang().is_err());
+    *faux_clock.lock().unwrap() = 10;
+    assert!(on_off.bang().is_ok());
+
+    assert!(on_off.bang_at(u32::MAX - 1).is_err());
+    assert!(on_off.bang_at(u32::MAX).is_err());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn last_error_tracks_most_recent_attempt() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    assert!(on_off.last_error().is_none());
+
+    *faux_clock.lock().unwrap() = 4;
+    let _ = on_off.bang();
+    match on_off.last_error() {
+        Some(TransitionError::Constrained { remaining, .. }) => {
+            assert_eq!(*remaining, Duration::from_millis(6))
+        }
+        _ => panic!("expected the transition to be constrained"),
+    }
+
+    *faux_clock.lock().unwrap() = 10;
+    let _ = on_off.bang();
+    assert!(on_off.last_error().is_none());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn tolerance_absorbs_clock_jitter() {
+    let now = || 0;
+    let minimum = Some(Duration::from_millis(10));
+
+    let mut exact = TimeConstrainedOnOff::new(true, None, None, minimum, None, &now);
+    let mut tolerant = TimeConstrainedOnOff::new(true, None, None, minimum, None, &now)
+        .with_tolerance(Duration::from_millis(2));
+
+    assert!(tolerant.bang_at(7).is_err());
+    assert_eq!(
+        tolerant.simulate_bang(7),
+        SimResult::BlockedByConstraint {
+            remaining: Duration::from_millis(1)
+        }
+    );
+
+    assert!(exact.bang_at(9).is_err());
+    assert!(tolerant.bang_at(9).is_ok());
+}
+
+#[test]
+fn active_minimum_follows_state() {
+    let now = || 0;
+    let minimum_on = Some(Duration::from_millis(10));
+    let minimum_off = Some(Duration::from_millis(20));
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, minimum_on, minimum_off, &now)
+        .with_proportional_off(4.0);
+
+    assert_eq!(on_off.active_minimum(), minimum_on);
+
+    assert!(on_off.bang_at(10).is_ok());
+    // ten milliseconds on extends the following off period to forty
+    assert_eq!(on_off.active_minimum(), Some(Duration::from_millis(40)));
+
+    assert!(on_off.bang_at(50).is_ok());
+    assert_eq!(on_off.active_minimum(), minimum_on);
+
+    assert!(on_off.bang_at(60).is_ok());
+    assert!(on_off.bang_at(100).is_ok());
+    assert!(on_off.bang_at(101).is_err());
+    assert_eq!(on_off.active_minimum(), minimum_on);
+
+    on_off.set_minimum_on(None);
+    assert_eq!(on_off.active_minimum(), None);
+}
+
+#[test]
+fn force_toggle_ignores_constraints_but_not_handlers() {
+    let now = || 0;
+
+    let veto = Arc::new(Mutex::new(false));
+    let veto_inner = Arc::clone(&veto);
+    let mut handle_on = move || {
+        if *veto_inner.lock().unwrap() {
+            Err(BangBangError::StateChangeTemporarilyConstrained {
+                from: BangBangState::A,
+                to: BangBangState::B,
+                code: 7,
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    let minimum = Some(Duration::from_millis(10));
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, Some(&mut handle_on), None, minimum, minimum, &now);
+
+    assert!(on_off.bang().is_err());
+    assert!(on_off.force_toggle().is_ok());
+    assert_eq!(on_off.is_off(), true);
+    // the forced transition restarted the minimum of the new state
+    assert_eq!(on_off.time_remaining(), minimum);
+
+    *veto.lock().unwrap() = true;
+    assert!(on_off.force_toggle().is_err());
+    assert_eq!(on_off.is_off(), true);
+
+    *veto.lock().unwrap() = false;
+    assert!(on_off.force_toggle().is_ok());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn on_ready_fires_once_per_window() {
+    let ready = Arc::new(Mutex::new(Vec::new()));
+    let ready_inner = Arc::clone(&ready);
+    let mut on_ready = move |state| ready_inner.lock().unwrap().push(state);
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(20)),
+        &now,
+    )
+    .with_on_ready(&mut on_ready);
+
+    assert!(on_off.bang_at(5).is_err());
+    assert!(on_off.poll_at(9).is_ok());
+    assert!(ready.lock().unwrap().is_empty());
+
+    assert!(on_off.poll_at(10).is_ok());
+    assert!(on_off.poll_at(11).is_ok());
+    assert_eq!(*ready.lock().unwrap(), vec![BangBangState::A]);
+
+    assert!(on_off.bang_at(12).is_ok());
+    assert!(on_off.bang_at(31).is_err());
+    assert_eq!(ready.lock().unwrap().len(), 1);
+
+    // observed by the transition itself, which then proceeds
+    assert!(on_off.bang_at(32).is_ok());
+    assert_eq!(
+        *ready.lock().unwrap(),
+        vec![BangBangState::A, BangBangState::B]
+    );
+}
+
+#[test]
+fn gated_time_ignores_clock() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        &now,
+    )
+    .with_gated_time();
+
+    *faux_clock.lock().unwrap() = 1_000;
+    assert!(on_off.bang().is_err());
+
+    on_off.add_active_time(Duration::from_millis(9));
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(1)));
+
+    on_off.add_active_time(Duration::from_millis(1));
+    assert!(on_off.bang().is_ok());
+    assert!(on_off.bang().is_ok());
+
+    // active time restarts from zero at each transition
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(10)));
+}
+
+#[test]
+fn symmetric_minimum_constrains_both_states() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::with_symmetric_minimum(
+        true,
+        None,
+        None,
+        Duration::from_millis(10),
+        &now,
+    );
+    assert_eq!(on_off.minimum_on(), on_off.minimum_off());
+
+    assert!(on_off.bang_at(9).is_err());
+    assert!(on_off.bang_at(10).is_ok());
+    assert!(on_off.bang_at(19).is_err());
+    assert!(on_off.bang_at(20).is_ok());
+
+    on_off.set_minimum_both(Some(Duration::from_millis(5)));
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(5)));
+    assert_eq!(on_off.minimum_off(), Some(Duration::from_millis(5)));
+    assert!(on_off.bang_at(24).is_err());
+    assert!(on_off.bang_at(25).is_ok());
+    assert!(on_off.bang_at(29).is_err());
+    assert!(on_off.bang_at(30).is_ok());
+}
+
+#[test]
+fn relative_minimum_off_follows_each_on_period() {
+    let now = || 0;
+
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, None, None, None, Some(Duration::from_millis(5)), &now)
+            .relative_minimum_off(0.5);
+
+    // a short on period leaves the absolute minimum in charge
+    assert!(on_off.bang_at(4).is_ok());
+    assert!(on_off.bang_at(8).is_err());
+    assert!(on_off.bang_at(9).is_ok());
+
+    // a long on period gets a proportionally long off period
+    assert!(on_off.bang_at(109).is_ok());
+    assert_eq!(on_off.active_minimum(), Some(Duration::from_millis(50)));
+    assert!(on_off.bang_at(158).is_err());
+    assert!(on_off.bang_at(159).is_ok());
+
+    // and it applies again after the next on period
+    assert!(on_off.bang_at(179).is_ok());
+    assert_eq!(on_off.active_minimum(), Some(Duration::from_millis(10)));
+}
+
+#[test]
+fn proportional_off_measures_on_period_when_timer_kept_running() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, None, &now)
+        .with_proportional_off(1.0)
+        .with_timer_reset_policy(TimerResetPolicy::OnToOff);
+
+    // turning on does not restart the timer, yet only the 10ms spent on count
+    assert!(on_off.bang_at(100).is_ok());
+    assert!(on_off.bang_at(110).is_ok());
+    assert_eq!(on_off.active_minimum(), Some(Duration::from_millis(10)));
+    assert!(on_off.bang_at(119).is_err());
+    assert!(on_off.bang_at(120).is_ok());
+}
+
+#[test]
+fn veto_predicate_predicts_without_handler() {
+    let handler_calls = Arc::new(Mutex::new(0));
+    let handler_calls_inner = Arc::clone(&handler_calls);
+    let mut handle_off = move || {
+        *handler_calls_inner.lock().unwrap() += 1;
+        Ok(())
+    };
+    let interlocked = |state| state == BangBangState::A;
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        Some(&mut handle_off),
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    on_off.set_veto_predicate(Some(&interlocked));
+
+    // time constraints are reported first
+    assert_eq!(on_off.can_set(BangBangState::A), false);
+    assert_eq!(
+        on_off.simulate_bang(5),
+        SimResult::BlockedByConstraint {
+            remaining: Duration::from_millis(5)
+        }
+    );
+    assert_eq!(
+        on_off.simulate_bang(10),
+        SimResult::LikelyVetoed(BangBangState::A)
+    );
+
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(*handler_calls.lock().unwrap(), 0);
+
+    on_off.set_veto_predicate(None);
+    assert_eq!(
+        on_off.simulate_bang(10),
+        SimResult::WouldTransition(BangBangState::A)
+    );
+}
+
+#[test]
+fn timer_reset_policy_limits_restarting_transitions() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(10)),
+        &now,
+    )
+    .with_timer_reset_policy(TimerResetPolicy::OffToOn);
+
+    // turning on restarts the timer, so the minimum on time is measured from 10
+    assert!(on_off.bang_at(10).is_ok());
+    *faux_clock.lock().unwrap() = 15;
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(5)));
+
+    // turning off does not, so the minimum off time is also measured from 10
+    assert!(on_off.bang_at(20).is_ok());
+    *faux_clock.lock().unwrap() = 20;
+    assert_eq!(on_off.time_remaining(), None);
+    assert!(on_off.bang_at(20).is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(10)));
+}
+
+#[test]
+fn timing_hysteresis_raises_boundary_after_block() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(10)),
+        &now,
+    )
+    .with_timing_hysteresis(Duration::from_millis(3));
+
+    // unblocked, so the minimum alone applies
+    assert!(on_off.bang_at(10).is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    // blocked once, so the boundary is now 13ms after the transition
+    assert!(on_off.bang_at(19).is_err());
+    assert!(on_off.bang_at(20).is_err());
+    assert!(on_off.bang_at(22).is_err());
+    assert!(on_off.bang_at(23).is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    // the transition lowered the boundary back to the minimum
+    assert!(on_off.bang_at(33).is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn timing_hysteresis_included_in_first_remaining() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_timing_hysteresis(Duration::from_millis(3));
+
+    let remaining = match on_off.bang_at(4) {
+        Err(TransitionError::Constrained { remaining, .. }) => remaining,
+        result => panic!("expected a constrained transition, got {:?}", result),
+    };
+    assert_eq!(remaining, Duration::from_millis(9));
+
+    // sleeping for exactly the reported time is enough
+    assert!(on_off.bang_at(4 + remaining.as_millis() as u32).is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn timing_hysteresis_saturates() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_timing_hysteresis(Duration::MAX);
+
+    assert!(on_off.bang_at(4).is_err());
+    assert!(on_off.bang_at(5).is_err());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn transition_errors_report_stable_codes_and_kinds() {
+    let vetoed = BangBangError::StateChangeTemporarilyConstrained {
+        from: BangBangState::B,
+        to: BangBangState::A,
+        code: 7,
+    };
+    let errors = [
+        TransitionError::Constrained {
+            from: BangBangState::B,
+            to: BangBangState::A,
+            remaining: Duration::from_millis(5),
+        },
+        TransitionError::Aborted {
+            from: BangBangState::B,
+            to: BangBangState::A,
+        },
+        TransitionError::UpstreamNotReady {
+            from: BangBangState::A,
+            to: BangBangState::B,
+        },
+        TransitionError::Vetoed(vetoed),
+        TransitionError::Interlocked {
+            from: BangBangState::A,
+            to: BangBangState::B,
+        },
+    ];
+
+    for (error, &kind) in errors.iter().zip(ErrorKind::ALL.iter()) {
+        assert_eq!(error.kind(), kind);
+        assert_eq!(error.code(), kind.code());
+    }
+    let codes: Vec<u16> = errors.iter().map(TransitionError::code).collect();
+    assert_eq!(codes, vec![0, 1, 2, 3, 4]);
+
+    // the codes carry over to the upstream error, except for vetoes which pass through
+    assert!(matches!(
+        BangBangError::from(errors[2]),
+        BangBangError::StateChangeTemporarilyConstrained { code: 2, .. }
+    ));
+    assert!(matches!(
+        BangBangError::from(errors[3]),
+        BangBangError::StateChangeTemporarilyConstrained { code: 7, .. }
+    ));
+}
+
+#[test]
+fn settling_blocks_transitions_until_settled() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(5)),
+        None,
+        &now,
+    )
+    .with_settling(Duration::from_millis(20));
+    assert_eq!(on_off.is_settled(), false);
+
+    *faux_clock.lock().unwrap() = 20;
+    assert_eq!(on_off.is_settled(), true);
+    assert!(on_off.bang().is_ok());
+
+    // the commanded state is reported straight away, but has not settled
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(on_off.is_settled(), false);
+
+    // the minimum on time has passed, but settling has not
+    *faux_clock.lock().unwrap() = 30;
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_millis(10)));
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    *faux_clock.lock().unwrap() = 40;
+    assert_eq!(on_off.is_settled(), true);
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn conditional_minimum_only_applies_while_condition_holds() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let now = || 0;
+    let loaded = Arc::new(AtomicBool::new(true));
+    let loaded_inner = Arc::clone(&loaded);
+    let condition = move || loaded_inner.load(Ordering::SeqCst);
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(10)),
+        &now,
+    )
+    .conditional_minimum(&condition);
+
+    assert!(on_off.bang_at(5).is_err());
+    assert_eq!(on_off.is_on(), true);
+
+    loaded.store(false, Ordering::SeqCst);
+    assert!(on_off.bang_at(5).is_ok());
+    assert!(on_off.bang_at(6).is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    loaded.store(true, Ordering::SeqCst);
+    assert!(on_off.bang_at(7).is_err());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn cmp_ready_orders_readiest_first() {
+    let now = || 100;
+
+    let mut controllers = [
+        TimeConstrainedOnOff::new(
+            true,
+            None,
+            None,
+            Some(Duration::from_millis(30)),
+            None,
+            &now,
+        ),
+        TimeConstrainedOnOff::new(true, None, None, None, None, &now),
+        TimeConstrainedOnOff::new(
+            true,
+            None,
+            None,
+            Some(Duration::from_millis(10)),
+            None,
+            &now,
+        ),
+    ];
+    assert_eq!(controllers[0].next_allowed_at(), 130);
+    assert_eq!(controllers[1].next_allowed_at(), 100);
+
+    controllers.sort_by(|a, b| a.cmp_ready(b));
+    let minimums: Vec<_> = controllers.iter().map(|c| c.minimum_on()).collect();
+    assert_eq!(
+        minimums,
+        vec![
+            None,
+            Some(Duration::from_millis(10)),
+            Some(Duration::from_millis(30))
+        ]
+    );
+}
+
+#[test]
+fn cmp_ready_orders_across_clock_wrap() {
+    let now = || u32::MAX - 5;
+
+    let before_wrap =
+        TimeConstrainedOnOff::new(true, None, None, Some(Duration::from_millis(2)), None, &now);
+    let after_wrap = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(20)),
+        None,
+        &now,
+    );
+    assert!(after_wrap.next_allowed_at() < before_wrap.next_allowed_at());
+
+    assert_eq!(
+        before_wrap.cmp_ready(&after_wrap),
+        core::cmp::Ordering::Less
+    );
+}
+
+#[test]
+fn fast_forward_and_bang_advances_to_boundary() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let mut advance = |milliseconds| *faux_clock.lock().unwrap() += milliseconds;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(25)),
+        &now,
+    );
+
+    assert!(on_off.fast_forward_and_bang(&mut advance).is_ok());
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(now(), 10);
+
+    assert!(on_off.fast_forward_and_bang(&mut advance).is_ok());
+    assert_eq!(on_off.is_on(), true);
+    assert_eq!(now(), 35);
+}
+
+#[test]
+fn scales_and_restores_minimums() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(100)),
+        Some(Duration::from_millis(50)),
+        &now,
+    );
+
+    on_off.scale_minimums(0.1);
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(10)));
+    assert!(on_off.bang_at(10).is_ok());
+    assert!(on_off.bang_at(15).is_ok());
+
+    // scaling again starts from the original minimums
+    on_off.scale_minimums(0.5);
+    assert_eq!(on_off.minimum_off(), Some(Duration::from_millis(25)));
+
+    on_off.restore_minimums();
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(100)));
+    assert_eq!(on_off.minimum_off(), Some(Duration::from_millis(50)));
+    assert!(on_off.bang_at(114).is_err());
+    assert!(on_off.bang_at(115).is_ok());
+}
+
+#[test]
+fn minimum_set_while_scaled_survives_restore() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(100)),
+        Some(Duration::from_millis(50)),
+        &now,
+    );
+
+    on_off.scale_minimums(0.1);
+    on_off.set_minimum_on(Some(Duration::from_millis(30)));
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(30)));
+    assert_eq!(on_off.minimum_off(), Some(Duration::from_millis(5)));
+
+    // the directly set minimum wins, the other returns to its original value
+    on_off.restore_minimums();
+    assert_eq!(on_off.minimum_on(), Some(Duration::from_millis(30)));
+    assert_eq!(on_off.minimum_off(), Some(Duration::from_millis(50)));
+}
+
+#[test]
+fn into_unconstrained_toggles_freely_and_rewraps() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let on_calls = Arc::new(Mutex::new(0));
+    let on_calls_inner = Arc::clone(&on_calls);
+    let mut handle_on = move || {
+        *on_calls_inner.lock().unwrap() += 1;
+        Ok(())
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        Some(&mut handle_on),
+        None,
+        Some(faux_ten_milliseconds),
+        Some(faux_ten_milliseconds),
+        &now,
+    );
+    *faux_clock.lock().unwrap() = 10;
+    assert!(on_off.bang().is_ok());
+    assert_eq!(*on_calls.lock().unwrap(), 1);
+
+    let mut plain = on_off.into_unconstrained();
+    assert_eq!(plain.is_on(), true);
+
+    // no time has passed, yet nothing blocks the transitions
+    assert!(plain.bang().is_ok());
+    assert!(plain.bang().is_ok());
+    assert!(plain.bang().is_ok());
+    assert_eq!(plain.is_off(), true);
+    assert_eq!(*on_calls.lock().unwrap(), 2);
+
+    let mut on_off = TimeConstrainedOnOff::from_onoff(
+        plain,
+        Some(faux_ten_milliseconds),
+        Some(faux_ten_milliseconds),
+        &now,
+    );
+    assert_eq!(on_off.is_off(), true);
+    assert!(on_off.bang().is_err());
+
+    *faux_clock.lock().unwrap() = 20;
+    assert!(on_off.bang().is_ok());
+    assert_eq!(*on_calls.lock().unwrap(), 3);
+}
+
+#[test]
+fn into_unconstrained_keeps_handlers_of_wrapped_controller() {
+    let now = || 0;
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let on_calls = Arc::clone(&calls);
+    let mut wrapped_on = move || {
+        on_calls.lock().unwrap().push("wrapped on");
+        Ok(())
+    };
+    let off_calls = Arc::clone(&calls);
+    let mut wrapped_off = move || {
+        off_calls.lock().unwrap().push("wrapped off");
+        Ok(())
+    };
+    let extra_calls = Arc::clone(&calls);
+    let mut extra_off = move || {
+        extra_calls.lock().unwrap().push("extra off");
+        Ok(())
+    };
+
+    let plain = OnOff::new(false, Some(&mut wrapped_on), Some(&mut wrapped_off));
+    let mut on_off = TimeConstrainedOnOff::from_onoff(plain, None, None, &now);
+    on_off.set_off_handler(Some(&mut extra_off));
+
+    let mut plain = on_off.into_unconstrained();
+    assert!(plain.bang().is_ok());
+    assert!(plain.bang().is_ok());
+    assert_eq!(*calls.lock().unwrap(), ["wrapped on", "wrapped off"]);
+}
+
+#[test]
+fn bang_timed_returns_dwell_of_state_left() {
+    let faux_clock = Arc::new(Mutex::new(100 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 125;
+    assert_eq!(on_off.bang_timed().unwrap(), Duration::from_millis(25));
+
+    *faux_clock.lock().unwrap() = 130;
+    assert!(matches!(
+        on_off.bang_timed(),
+        Err(TransitionError::Constrained { .. })
+    ));
+    assert_eq!(on_off.is_on(), true);
+
+    *faux_clock.lock().unwrap() = 142;
+    assert_eq!(on_off.bang_timed().unwrap(), Duration::from_millis(17));
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn boundary_policy_at_exact_minimum() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut inclusive =
+        TimeConstrainedOnOff::new(false, None, None, None, Some(faux_ten_milliseconds), &now);
+    let mut exclusive =
+        TimeConstrainedOnOff::new(false, None, None, None, Some(faux_ten_milliseconds), &now)
+            .with_boundary_policy(BoundaryPolicy::Exclusive);
+
+    *faux_clock.lock().unwrap() = 10;
+    assert!(inclusive.bang().is_ok());
+    assert!(matches!(
+        exclusive.try_bang(),
+        Err(TransitionError::Constrained { remaining, .. }) if remaining == Duration::from_millis(1)
+    ));
+    assert_eq!(exclusive.is_off(), true);
+
+    *faux_clock.lock().unwrap() = 11;
+    assert!(exclusive.bang().is_ok());
+    assert_eq!(exclusive.is_on(), true);
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "fugit")]
+
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use fugit::{ExtU32, MillisDurationU32};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn fugit_constraints_match_core_time() {
+    let now = || 0;
+
+    let mut from_fugit =
+        TimeConstrainedOnOff::new_fugit(true, None, None, Some(10.millis()), Some(1.secs()), &now);
+    let mut from_core = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_secs(1)),
+        &now,
+    );
+
+    assert_eq!(from_fugit.minimum_on(), from_core.minimum_on());
+    assert_eq!(from_fugit.minimum_off(), from_core.minimum_off());
+    assert_eq!(from_fugit.minimum_off_fugit(), Some(1_000.millis()));
+    assert_eq!(
+        from_fugit.time_remaining_fugit(),
+        Some(MillisDurationU32::from_ticks(10))
+    );
+
+    for &at in &[9, 10, 1_009, 1_010] {
+        assert_eq!(
+            from_fugit.bang_at(at).is_ok(),
+            from_core.bang_at(at).is_ok()
+        );
+        assert_eq!(from_fugit.state(), from_core.state());
+    }
+    assert_eq!(from_fugit.is_on(), true);
+}
+
+#[test]
+fn fugit_remaining_rounds_up_fractions() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_micros(10_500)),
+        None,
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 10;
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_micros(500)));
+    assert_eq!(on_off.time_remaining_fugit(), Some(1.millis()));
+}
@@ -0,0 +1,129 @@
+use bangbang_timed::prelude::*;
+use bangbang_timed::HISTORY_CAPACITY;
+use core::time::Duration;
+
+#[test]
+fn counts_transitions_within_window() {
+    let now = || 100;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    assert_eq!(on_off.transitions_within(Duration::from_millis(1_000)), 0);
+
+    for &at in &[10, 20, 50, 90] {
+        assert!(on_off.bang_at(at).is_ok());
+    }
+
+    assert_eq!(on_off.transitions_within(Duration::from_millis(0)), 0);
+    assert_eq!(on_off.transitions_within(Duration::from_millis(30)), 1);
+    assert_eq!(on_off.transitions_within(Duration::from_millis(60)), 2);
+    assert_eq!(on_off.transitions_within(Duration::from_millis(100)), 4);
+}
+
+#[test]
+fn transitions_within_saturates_at_capacity() {
+    let now = || 100;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    for at in 0..(HISTORY_CAPACITY as u32 + 5) {
+        assert!(on_off.bang_at(at).is_ok());
+    }
+
+    assert_eq!(
+        on_off.transitions_within(Duration::from_millis(1_000)),
+        HISTORY_CAPACITY as u32
+    );
+}
+
+#[test]
+fn on_ratio_over_reconstructs_duty_cycle() {
+    use std::sync::{Arc, Mutex};
+
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    // no history, so the whole window is attributed to the current state
+    assert!((on_off.on_ratio_over(Duration::from_millis(100)) - 1.0).abs() < 1e-6);
+
+    // on until 10, off until 30, on until 40
+    assert!(on_off.bang_at(10).is_ok());
+    assert!(on_off.bang_at(30).is_ok());
+    *faux_clock.lock().unwrap() = 40;
+
+    assert!((on_off.on_ratio_over(Duration::from_millis(10)) - 1.0).abs() < 1e-6);
+    assert!((on_off.on_ratio_over(Duration::from_millis(20)) - 0.5).abs() < 1e-6);
+    assert!((on_off.on_ratio_over(Duration::from_millis(25)) - 0.4).abs() < 1e-6);
+    assert!((on_off.on_ratio_over(Duration::from_millis(40)) - 0.5).abs() < 1e-6);
+    assert_eq!(on_off.on_ratio_over(Duration::from_millis(0)), 1.0);
+
+    assert!(on_off.bang_at(40).is_ok());
+    assert_eq!(on_off.on_ratio_over(Duration::from_millis(0)), 0.0);
+}
+
+#[test]
+fn exports_history_oldest_first() {
+    let now = || 100;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    let mut empty = String::new();
+    assert!(on_off.export_history(&mut empty).is_ok());
+    assert_eq!(empty, "");
+
+    for &at in &[10, 20, 50] {
+        assert!(on_off.bang_at(at).is_ok());
+    }
+
+    let mut log = String::new();
+    assert!(on_off.export_history(&mut log).is_ok());
+    assert_eq!(log, "10,off\n20,on\n50,off\n");
+}
+
+#[test]
+fn duty_since_checkpoint_ignores_earlier_time() {
+    use std::sync::{Arc, Mutex};
+
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    // on until 30, off until 40
+    assert!(on_off.bang_at(30).is_ok());
+    *faux_clock.lock().unwrap() = 40;
+    assert!((on_off.duty_since_checkpoint() - 0.75).abs() < 1e-6);
+
+    // nothing has happened since the checkpoint, so the current state is reported
+    on_off.checkpoint();
+    assert_eq!(on_off.duty_since_checkpoint(), 0.0);
+
+    // off until 50, on until 80, off until 90
+    assert!(on_off.bang_at(50).is_ok());
+    assert!(on_off.bang_at(80).is_ok());
+    *faux_clock.lock().unwrap() = 90;
+    assert!((on_off.duty_since_checkpoint() - 0.6).abs() < 1e-6);
+}
+
+#[test]
+fn tracks_latest_entry_into_each_state() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    assert_eq!(on_off.last_on_at(), None);
+    assert_eq!(on_off.last_off_at(), None);
+
+    assert!(on_off.bang_at(10).is_ok());
+    assert_eq!(on_off.last_on_at(), Some(10));
+    assert_eq!(on_off.last_off_at(), None);
+
+    assert!(on_off.bang_at(25).is_ok());
+    assert!(on_off.bang_at(40).is_ok());
+    assert!(on_off.bang_at(70).is_ok());
+    assert_eq!(on_off.last_on_at(), Some(40));
+    assert_eq!(on_off.last_off_at(), Some(70));
+}
@@ -0,0 +1,155 @@
+use bangbang_timed::prelude::*;
+
+fn count_toggles(controller: &mut HysteresisOnOff<'_>, measurements: &[f32]) -> usize {
+    let mut toggles = 0;
+    for &measurement in measurements {
+        let was_on = controller.controller().is_on();
+        assert!(controller.update(measurement).is_ok());
+        if controller.controller().is_on() != was_on {
+            toggles += 1;
+        }
+    }
+    toggles
+}
+
+#[test]
+fn switches_at_thresholds() {
+    let now = || 0;
+
+    let mut hysteresis = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+        19.5,
+        20.5,
+    );
+
+    assert!(hysteresis.update(20.0).is_ok());
+    assert_eq!(hysteresis.controller().is_on(), false);
+
+    assert!(hysteresis.update(19.5).is_ok());
+    assert_eq!(hysteresis.controller().is_on(), true);
+
+    assert!(hysteresis.update(20.4).is_ok());
+    assert_eq!(hysteresis.controller().is_on(), true);
+
+    assert!(hysteresis.update(20.5).is_ok());
+    assert_eq!(hysteresis.controller().is_on(), false);
+}
+
+#[test]
+fn filter_reduces_chatter() {
+    let now = || 0;
+
+    // noise around the setpoint that reaches beyond both edges of the deadband
+    let noisy: Vec<f32> = (0..100)
+        .map(|i| if i % 2 == 0 { 19.0 } else { 21.0 })
+        .collect();
+
+    let mut unfiltered = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+        19.5,
+        20.5,
+    );
+    let mut filtered = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+        19.5,
+        20.5,
+    )
+    .with_filter(0.1);
+
+    let unfiltered_toggles = count_toggles(&mut unfiltered, &noisy);
+    let filtered_toggles = count_toggles(&mut filtered, &noisy);
+
+    assert_eq!(unfiltered_toggles, 100);
+    assert!(filtered_toggles <= 2);
+    assert!((filtered.filtered().unwrap() - 20.0).abs() < 0.5);
+}
+
+#[test]
+fn update_reports_blocked_by_minimum() {
+    use core::time::Duration;
+
+    let now = || 0;
+
+    let mut hysteresis = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(
+            false,
+            None,
+            None,
+            Some(Duration::from_millis(10)),
+            None,
+            &now,
+        ),
+        19.5,
+        20.5,
+    );
+
+    assert_eq!(hysteresis.update(20.0).unwrap(), UpdateOutcome::InDeadband);
+    assert_eq!(hysteresis.update(21.0).unwrap(), UpdateOutcome::Unchanged);
+    assert_eq!(hysteresis.update(19.0).unwrap(), UpdateOutcome::TurnedOn);
+
+    // past the high threshold, but still within the minimum on time
+    assert_eq!(
+        hysteresis.update(21.0).unwrap(),
+        UpdateOutcome::BlockedByMinimum {
+            remaining: Duration::from_millis(10)
+        }
+    );
+    assert_eq!(hysteresis.controller().is_on(), true);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "must not be above the high threshold")]
+fn inverted_thresholds_trip_invariant() {
+    let now = || 0;
+
+    let _hysteresis = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+        20.5,
+        19.5,
+    );
+}
+
+#[test]
+fn nan_filter_leaves_measurements_unfiltered() {
+    let now = || 0;
+
+    let mut hysteresis = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+        19.5,
+        20.5,
+    )
+    .with_filter(f32::NAN);
+
+    assert_eq!(hysteresis.update(19.0).unwrap(), UpdateOutcome::TurnedOn);
+    assert_eq!(hysteresis.update(21.0).unwrap(), UpdateOutcome::TurnedOff);
+    assert_eq!(hysteresis.filtered(), Some(21.0));
+}
+
+#[test]
+fn non_finite_measurements_are_discarded() {
+    let now = || 0;
+
+    let mut hysteresis = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+        19.5,
+        20.5,
+    )
+    .with_filter(0.5);
+
+    assert_eq!(hysteresis.update(20.0).unwrap(), UpdateOutcome::InDeadband);
+    assert_eq!(
+        hysteresis.update(f32::NAN).unwrap(),
+        UpdateOutcome::NotFinite
+    );
+    assert_eq!(
+        hysteresis.update(f32::NEG_INFINITY).unwrap(),
+        UpdateOutcome::NotFinite
+    );
+    assert_eq!(hysteresis.filtered(), Some(20.0));
+    assert_eq!(hysteresis.controller().is_off(), true);
+
+    // the filter carries on from the last finite measurement
+    assert_eq!(hysteresis.update(18.0).unwrap(), UpdateOutcome::TurnedOn);
+    assert_eq!(hysteresis.filtered(), Some(19.0));
+}
@@ -0,0 +1,126 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn refuses_heater_while_cooler_is_on() {
+    let now = || 0;
+
+    let mut heater = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    let mut cooler = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    let mut interlock = Interlock::new([&mut heater, &mut cooler]);
+
+    match interlock.request_on(0) {
+        Err(InterlockError::Transition(TransitionError::Interlocked {
+            from: BangBangState::A,
+            to: BangBangState::B,
+        })) => {}
+        _ => panic!("expected the heater to be interlocked"),
+    }
+    assert_eq!(interlock.controllers()[0].is_off(), true);
+
+    assert!(interlock.request_off(1).is_ok());
+    assert!(interlock.request_on(0).is_ok());
+    assert_eq!(interlock.controllers()[0].is_on(), true);
+}
+
+#[test]
+fn switches_cooler_off_before_heater_on() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut heater = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    let mut cooler = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    let mut interlock =
+        Interlock::new([&mut heater, &mut cooler]).with_policy(InterlockPolicy::SwitchOthersOff);
+
+    // the cooler has not been on for its minimum, so neither changes
+    let error = interlock.request_on(0).unwrap_err();
+    assert_eq!(error.transition().unwrap().is_constrained(), true);
+    assert_eq!(interlock.controllers()[0].is_off(), true);
+    assert_eq!(interlock.controllers()[1].is_on(), true);
+
+    *faux_clock.lock().unwrap() = 10;
+    assert!(interlock.request_on(0).is_ok());
+    assert_eq!(interlock.controllers()[0].is_on(), true);
+    assert_eq!(interlock.controllers()[1].is_off(), true);
+}
+
+#[test]
+fn refusal_reports_state_of_requested_controller() {
+    let now = || 0;
+
+    let mut heater = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    let mut cooler = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    let mut interlock = Interlock::new([&mut heater, &mut cooler]);
+
+    match interlock.request_on(0) {
+        Err(InterlockError::Transition(TransitionError::Interlocked {
+            from: BangBangState::B,
+            to: BangBangState::B,
+        })) => {}
+        _ => panic!("expected the interlock to report the heater as on"),
+    }
+}
+
+#[test]
+fn blocked_target_leaves_others_on() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut heater = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        None,
+        Some(Duration::from_millis(20)),
+        &now,
+    );
+    let mut cooler = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    let mut interlock =
+        Interlock::new([&mut heater, &mut cooler]).with_policy(InterlockPolicy::SwitchOthersOff);
+
+    // the heater has not been off for its minimum, so the cooler is left on
+    *faux_clock.lock().unwrap() = 10;
+    let error = interlock.request_on(0).unwrap_err();
+    assert_eq!(
+        error.transition().unwrap().remaining(),
+        Some(Duration::from_millis(10))
+    );
+    assert_eq!(interlock.controllers()[0].is_off(), true);
+    assert_eq!(interlock.controllers()[1].is_on(), true);
+
+    *faux_clock.lock().unwrap() = 20;
+    assert!(interlock.request_on(0).is_ok());
+    assert_eq!(interlock.controllers()[0].is_on(), true);
+    assert_eq!(interlock.controllers()[1].is_off(), true);
+}
+
+#[test]
+fn unknown_index_is_an_error() {
+    let now = || 0;
+
+    let mut heater = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    let mut cooler = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    let mut interlock = Interlock::new([&mut heater, &mut cooler]);
+
+    match interlock.request_on(2) {
+        Err(InterlockError::NoSuchController(2)) => {}
+        _ => panic!("expected index 2 to be rejected"),
+    }
+    match interlock.request_off(2) {
+        Err(InterlockError::NoSuchController(2)) => {}
+        _ => panic!("expected index 2 to be rejected"),
+    }
+    assert_eq!(interlock.controllers()[0].is_off(), true);
+    assert_eq!(interlock.controllers()[1].is_off(), true);
+}
@@ -0,0 +1,157 @@
+use bangbang_timed::prelude::*;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::time::Duration;
+
+static MIRROR: AtomicBool = AtomicBool::new(false);
+static CLOCK: AtomicU32 = AtomicU32::new(0);
+
+/// small deterministic generator so the sequence is reproducible without extra dependencies
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        ((self.0 >> 33) as u32) % bound
+    }
+}
+
+#[test]
+fn randomized_commands_keep_invariants() {
+    // the clock follows the time of each command, so resets happen at the same time
+    let now = || CLOCK.load(Ordering::SeqCst);
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(20)),
+        &now,
+    )
+    .with_proportional_off(0.5)
+    .with_stall_detection(3)
+    .with_state_mirror(&MIRROR);
+    on_off.set_maximum_on(Some(Duration::from_millis(200)));
+
+    let mut rng = Lcg(42);
+    let mut at = 0u32;
+
+    for _ in 0..10_000 {
+        at += rng.next(8);
+        CLOCK.store(at, Ordering::SeqCst);
+        let before = on_off.state();
+        let was_constrained = matches!(
+            on_off.simulate_bang(at),
+            SimResult::BlockedByConstraint { .. }
+        );
+
+        match rng.next(6) {
+            0 | 1 => {
+                let _ = on_off.bang_at(at);
+                if was_constrained {
+                    assert_eq!(on_off.state(), before);
+                }
+            }
+            2 => {
+                let _ = on_off.step(at, Some(Command::Toggle));
+            }
+            3 => {
+                let _ = on_off.poll_at(at);
+            }
+            4 => {
+                let minimum = Duration::from_millis(u64::from(rng.next(30)));
+                on_off.set_minimum_off(Some(minimum));
+            }
+            _ => {
+                let state = if rng.next(2) == 0 {
+                    BangBangState::A
+                } else {
+                    BangBangState::B
+                };
+                assert!(on_off.reset_to(state, false).is_ok());
+            }
+        }
+
+        assert_eq!(on_off.check_invariants(), Ok(()));
+    }
+}
+
+#[test]
+#[cfg(not(feature = "debug-strict"))]
+fn detects_minimum_bypassed() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_gated_time();
+
+    // more active time than the clock shows has passed lets the transition through early
+    on_off.add_active_time(Duration::from_millis(10));
+    assert!(on_off.bang_at(0).is_ok());
+    assert_eq!(
+        on_off.check_invariants(),
+        Err(InvariantViolation::MinimumBypassed)
+    );
+}
+
+#[test]
+fn detects_last_changed_regressing() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    assert!(on_off.bang_at(200).is_ok());
+    assert_eq!(on_off.check_invariants(), Ok(()));
+
+    // the clock restarted behind the controller's back
+    assert!(on_off.bang_at(50).is_ok());
+    assert_eq!(
+        on_off.check_invariants(),
+        Err(InvariantViolation::LastChangedRegressed)
+    );
+}
+
+#[test]
+fn restored_counters_keep_invariants() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    // counters persisted before a restart, with no history to back them
+    on_off.restore_counters(42, 7);
+    assert_eq!(on_off.check_invariants(), Ok(()));
+
+    assert!(on_off.bang_at(10).is_ok());
+    assert_eq!(on_off.metrics().transitions, 43);
+    assert_eq!(on_off.check_invariants(), Ok(()));
+
+    // restoring again, this time below the retained history
+    on_off.restore_counters(0, 0);
+    assert!(on_off.bang_at(20).is_ok());
+    assert_eq!(on_off.check_invariants(), Ok(()));
+}
+
+#[test]
+fn detects_time_disagreeing_with_history() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+
+    assert!(on_off.bang_at(10).is_ok());
+    // restarting the timer of a running controller loses the time already spent `on`
+    on_off.start(30);
+    assert!(on_off.bang_at(50).is_ok());
+    assert_eq!(
+        on_off.check_invariants(),
+        Err(InvariantViolation::TimeDisagreesWithHistory)
+    );
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "log")]
+
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+
+static TIMING_TRACES: AtomicUsize = AtomicUsize::new(0);
+static CLOCK_ERRORS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingLogger;
+
+impl Log for CountingLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if record.level() == Level::Trace && format!("{}", record.args()).contains("time delta") {
+            let _ = TIMING_TRACES.fetch_add(1, Ordering::SeqCst);
+        }
+        if record.level() == Level::Error && format!("{}", record.args()).contains("backwards") {
+            let _ = CLOCK_ERRORS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CountingLogger = CountingLogger;
+static INSTALL_LOGGER: Once = Once::new();
+
+fn install_logger() {
+    INSTALL_LOGGER.call_once(|| {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+    });
+}
+
+#[test]
+fn log_timing_gates_timing_traces() {
+    install_logger();
+
+    let now = || 0;
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+
+    assert!(on_off.time_remaining().is_some());
+    let emitted = TIMING_TRACES.load(Ordering::SeqCst);
+    assert!(emitted > 0);
+
+    on_off.set_log_timing(false);
+    assert!(on_off.time_remaining().is_some());
+    assert!(on_off.bang().is_err());
+    assert_eq!(TIMING_TRACES.load(Ordering::SeqCst), emitted);
+
+    on_off.set_log_timing(true);
+    assert!(on_off.time_remaining().is_some());
+    assert!(TIMING_TRACES.load(Ordering::SeqCst) > emitted);
+}
+
+#[test]
+fn repeated_backward_jumps_escalate_to_error() {
+    install_logger();
+
+    let now = || 100;
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_clock_policy(ClockPolicy::Monotonic)
+    .with_backward_jump_threshold(2);
+    // tests run in parallel, keep timing traces out of the count of the test above
+    on_off.set_log_timing(false);
+
+    // a single jump back is logged as a warning however many readings stay behind
+    assert!(on_off.bang_at(50).is_err());
+    assert!(on_off.bang_at(60).is_err());
+    assert!(on_off.bang_at(70).is_err());
+    assert_eq!(on_off.backward_jump_count(), 1);
+    assert_eq!(CLOCK_ERRORS.load(Ordering::SeqCst), 0);
+
+    assert!(on_off.bang_at(20).is_err());
+    assert_eq!(on_off.backward_jump_count(), 2);
+    assert!(CLOCK_ERRORS.load(Ordering::SeqCst) > 0);
+}
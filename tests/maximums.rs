@@ -0,0 +1,252 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+
+#[test]
+fn projects_state_through_maximums() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    assert_eq!(on_off.projected_state_at(1_000), BangBangState::B);
+
+    on_off.set_maximum_on(Some(Duration::from_millis(10)));
+    assert_eq!(on_off.projected_state_at(9), BangBangState::B);
+    assert_eq!(on_off.projected_state_at(10), BangBangState::A);
+    // no maximum off, so it stays off forever once forced off
+    assert_eq!(on_off.projected_state_at(1_000), BangBangState::A);
+
+    on_off.set_maximum_off(Some(Duration::from_millis(30)));
+    assert_eq!(on_off.projected_state_at(39), BangBangState::A);
+    assert_eq!(on_off.projected_state_at(40), BangBangState::B);
+    assert_eq!(on_off.projected_state_at(49), BangBangState::B);
+    assert_eq!(on_off.projected_state_at(50), BangBangState::A);
+    assert_eq!(on_off.projected_state_at(80), BangBangState::B);
+    assert_eq!(on_off.projected_state_at(1_000), BangBangState::B);
+    assert_eq!(on_off.projected_state_at(1_010), BangBangState::A);
+
+    // projecting has no side effects
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn poll_forces_transition_at_maximum() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    on_off.set_maximum_on(Some(Duration::from_millis(10)));
+
+    assert_eq!(on_off.poll_at(9).unwrap(), None);
+    assert_eq!(on_off.is_on(), true);
+
+    assert_eq!(on_off.poll_at(10).unwrap(), Some(BangBangState::A));
+    assert_eq!(on_off.is_off(), true);
+
+    assert_eq!(on_off.poll_at(1_000).unwrap(), None);
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn waveform_follows_duty_cycle() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    on_off.set_maximum_on(Some(Duration::from_millis(20)));
+    on_off.set_maximum_off(Some(Duration::from_millis(30)));
+
+    let on = BangBangState::B;
+    let off = BangBangState::A;
+    let samples: Vec<_> = on_off.waveform(Duration::from_millis(10), 11).collect();
+
+    assert_eq!(
+        samples,
+        vec![
+            (0, on),
+            (10, on),
+            (20, off),
+            (30, off),
+            (40, off),
+            (50, on),
+            (60, on),
+            (70, off),
+            (80, off),
+            (90, off),
+            (100, on),
+        ]
+    );
+}
+
+#[test]
+fn validate_rejects_minimum_beyond_maximum() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::try_new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(20)),
+        Some(Duration::from_millis(20)),
+        &now,
+    )
+    .unwrap();
+
+    on_off.set_maximum_on(Some(Duration::from_millis(20)));
+    assert_eq!(on_off.validate(), Ok(()));
+
+    // a maximum set below its minimum trips a debug assertion, so raise the minimums instead
+    on_off.set_maximum_off(Some(Duration::from_millis(20)));
+    on_off.set_minimum_off(Some(Duration::from_millis(21)));
+    assert_eq!(
+        on_off.validate(),
+        Err(ConfigError::MinimumExceedsMaximum(BangBangState::A))
+    );
+
+    on_off.set_maximum_off(None);
+    on_off.set_minimum_on(Some(Duration::from_millis(25)));
+    assert_eq!(
+        on_off.validate(),
+        Err(ConfigError::MinimumExceedsMaximum(BangBangState::B))
+    );
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "maximum on time of 5ms is shorter than the minimum on time of 20ms")]
+fn maximum_below_minimum_trips_invariant() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(20)),
+        None,
+        &now,
+    );
+    on_off.set_maximum_on(Some(Duration::from_millis(5)));
+}
+
+#[test]
+fn validate_rejects_negative_proportional_factor() {
+    let now = || 0;
+
+    let on_off =
+        TimeConstrainedOnOff::new(true, None, None, None, None, &now).with_proportional_off(-1.0);
+    assert_eq!(
+        on_off.validate(),
+        Err(ConfigError::InvalidProportionalFactor)
+    );
+}
+
+#[test]
+fn poll_all_reports_forced_controllers() {
+    use std::sync::{Arc, Mutex};
+
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut bank = ControllerBank::new([
+        TimeConstrainedOnOff::new(true, None, None, None, None, &now),
+        TimeConstrainedOnOff::new(true, None, None, None, None, &now),
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now),
+    ]);
+    bank.controllers_mut()[0].set_maximum_on(Some(Duration::from_millis(10)));
+    bank.controllers_mut()[2].set_maximum_off(Some(Duration::from_millis(20)));
+
+    *faux_clock.lock().unwrap() = 9;
+    assert_eq!(bank.poll_all(), [None, None, None]);
+
+    *faux_clock.lock().unwrap() = 25;
+    assert_eq!(
+        bank.poll_all(),
+        [Some(BangBangState::A), None, Some(BangBangState::B)]
+    );
+
+    // the controller without a maximum never appears
+    *faux_clock.lock().unwrap() = 1_000;
+    assert_eq!(bank.poll_all()[1], None);
+    assert_eq!(bank.controllers()[1].is_on(), true);
+}
+
+#[test]
+fn config_round_trips_through_from_config() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(20)),
+        &now,
+    );
+    on_off.set_maximum_on(Some(Duration::from_millis(100)));
+
+    let config = on_off.config();
+    assert_eq!(config.on, true);
+    assert_eq!(config.maximum_off, None);
+
+    let sibling = TimeConstrainedOnOff::from_config(config, &now);
+    assert_eq!(sibling.config(), config);
+    assert_eq!(sibling, on_off);
+    assert_eq!(sibling.maximum_on(), Some(Duration::from_millis(100)));
+}
+
+#[test]
+fn config_renders_key_value_lines() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(500)),
+        None,
+        &now,
+    );
+    on_off.set_maximum_off(Some(Duration::from_secs(60)));
+
+    assert_eq!(
+        on_off.config().to_string(),
+        "on = false\nmin_on_ms = 500\nmax_off_ms = 60000\n"
+    );
+}
+
+#[test]
+fn counts_down_to_forced_transition() {
+    use std::sync::{Arc, Mutex};
+
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    assert_eq!(on_off.time_until_forced(), None);
+
+    on_off.set_maximum_on(Some(Duration::from_millis(30)));
+    assert_eq!(on_off.time_until_forced(), Some(Duration::from_millis(30)));
+
+    *faux_clock.lock().unwrap() = 25;
+    assert_eq!(on_off.time_until_forced(), Some(Duration::from_millis(5)));
+
+    *faux_clock.lock().unwrap() = 40;
+    assert_eq!(on_off.time_until_forced(), Some(Duration::from_millis(0)));
+    assert_eq!(on_off.poll().unwrap(), Some(BangBangState::A));
+
+    // no maximum applies to the off state
+    assert_eq!(on_off.time_until_forced(), None);
+}
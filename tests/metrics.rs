@@ -0,0 +1,202 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn metrics_snapshot_after_known_sequence() {
+    let faux_clock = Arc::new(Mutex::new(0));
+    let faux_clock_inner = faux_clock.clone();
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(30)),
+        None,
+        &now,
+    );
+
+    *faux_clock.lock().unwrap() = 10;
+    on_off.bang().unwrap();
+    *faux_clock.lock().unwrap() = 20;
+    assert!(on_off.bang().is_err());
+    *faux_clock.lock().unwrap() = 40;
+    on_off.bang().unwrap();
+    *faux_clock.lock().unwrap() = 50;
+
+    // on from 10 to 40 out of 50
+    assert_eq!(
+        on_off.metrics(),
+        Metrics {
+            transitions: 2,
+            on: false,
+            duty_cycle: 0.6,
+            blocked_attempts: 1,
+        }
+    );
+}
+
+#[cfg(feature = "metrics")]
+mod exporter {
+    use super::*;
+    use metrics::{
+        Counter, CounterFn, Gauge, GaugeFn, Histogram, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+    use std::collections::BTreeMap;
+
+    type Values = Arc<Mutex<BTreeMap<String, f64>>>;
+
+    struct Slot {
+        name: String,
+        values: Values,
+    }
+
+    impl Slot {
+        fn store(&self, value: f64) {
+            let _ = self.values.lock().unwrap().insert(self.name.clone(), value);
+        }
+
+        fn add(&self, delta: f64) {
+            *self
+                .values
+                .lock()
+                .unwrap()
+                .entry(self.name.clone())
+                .or_insert(0.0) += delta;
+        }
+    }
+
+    impl CounterFn for Slot {
+        fn increment(&self, value: u64) {
+            self.add(value as f64);
+        }
+
+        fn absolute(&self, value: u64) {
+            self.store(value as f64);
+        }
+    }
+
+    impl GaugeFn for Slot {
+        fn increment(&self, value: f64) {
+            self.add(value);
+        }
+
+        fn decrement(&self, value: f64) {
+            self.add(-value);
+        }
+
+        fn set(&self, value: f64) {
+            self.store(value);
+        }
+    }
+
+    #[derive(Default)]
+    struct Capture {
+        values: Values,
+    }
+
+    impl Capture {
+        fn slot(&self, key: &Key) -> Arc<Slot> {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+
+            Arc::new(Slot {
+                name: format!("{}{{{}}}", key.name(), labels.join(",")),
+                values: self.values.clone(),
+            })
+        }
+    }
+
+    impl Recorder for Capture {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.slot(key))
+        }
+
+        fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::from_arc(self.slot(key))
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn records_metrics_with_installed_recorder() {
+        let faux_clock = Arc::new(Mutex::new(0));
+        let faux_clock_inner = faux_clock.clone();
+        let now = move || faux_clock_inner.lock().unwrap().clone();
+
+        let mut on_off = TimeConstrainedOnOff::new(
+            false,
+            None,
+            None,
+            Some(Duration::from_millis(30)),
+            None,
+            &now,
+        );
+
+        on_off.bang().unwrap();
+        *faux_clock.lock().unwrap() = 10;
+        assert!(on_off.bang().is_err());
+        *faux_clock.lock().unwrap() = 40;
+
+        let capture = Capture::default();
+        metrics::with_local_recorder(&capture, || on_off.record_metrics("heater"));
+
+        let values = capture.values.lock().unwrap();
+        let exported: Vec<(&str, f64)> = values.iter().map(|(k, &v)| (k.as_str(), v)).collect();
+        assert_eq!(
+            exported,
+            [
+                (
+                    "bangbang_timed_blocked_attempts_total{controller=heater}",
+                    1.0
+                ),
+                ("bangbang_timed_duty_cycle{controller=heater}", 1.0),
+                ("bangbang_timed_on{controller=heater}", 1.0),
+                ("bangbang_timed_transitions_total{controller=heater}", 1.0),
+            ]
+        );
+    }
+}
+
+#[test]
+fn counters_saturate_instead_of_wrapping() {
+    let faux_clock = Arc::new(Mutex::new(0));
+    let faux_clock_inner = faux_clock.clone();
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(30)),
+        None,
+        &now,
+    );
+    on_off.restore_counters(u64::MAX - 1, u32::MAX - 1);
+    assert_eq!(on_off.counters_saturated(), false);
+
+    on_off.bang().unwrap();
+    assert!(on_off.bang().is_err());
+    assert!(on_off.bang().is_err());
+    *faux_clock.lock().unwrap() = 30;
+    on_off.bang().unwrap();
+
+    let metrics = on_off.metrics();
+    assert_eq!(metrics.transitions, u64::MAX);
+    assert_eq!(metrics.blocked_attempts, u32::MAX);
+    assert_eq!(on_off.counters_saturated(), true);
+
+    on_off.restore_counters(0, 0);
+    assert_eq!(on_off.counters_saturated(), false);
+}
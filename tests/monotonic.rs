@@ -0,0 +1,61 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+/// clock counting microseconds, finer than the millisecond resolution of the `u32` clock
+#[derive(Clone)]
+struct FauxMicros(Arc<Mutex<u64>>);
+
+impl MonotonicClock for FauxMicros {
+    type Instant = u64;
+
+    fn now(&self) -> u64 {
+        *self.0.lock().unwrap()
+    }
+
+    fn duration_since(&self, later: u64, earlier: u64) -> Duration {
+        Duration::from_micros(later.saturating_sub(earlier))
+    }
+}
+
+#[test]
+fn constrains_with_custom_clock() {
+    let micros = Arc::new(Mutex::new(0));
+    let clock = FauxMicros(Arc::clone(&micros));
+
+    let mut on_off = InstantOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_micros(1_500)),
+        None,
+        clock,
+    );
+
+    *micros.lock().unwrap() = 1_499;
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.time_remaining(), Some(Duration::from_micros(1)));
+
+    *micros.lock().unwrap() = 1_500;
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_off(), true);
+
+    // no constraint on the off state
+    assert!(on_off.bang().is_ok());
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn constrains_with_std_clock() {
+    use bangbang_timed::monotonic::StdClock;
+
+    let minimum = Duration::from_millis(20);
+    let mut on_off = InstantOnOff::new(false, None, None, None, Some(minimum), StdClock);
+
+    assert!(on_off.bang().is_err());
+    std::thread::sleep(minimum);
+    assert!(on_off.bang().is_ok());
+    assert_eq!(on_off.is_on(), true);
+}
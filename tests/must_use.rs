@@ -0,0 +1,5 @@
+#[test]
+fn ignored_results_warn() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/ignored_*.rs");
+}
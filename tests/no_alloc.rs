@@ -0,0 +1,85 @@
+//! the core controller must never allocate, so any allocation made by the thread under test while
+//! the trap is armed is counted and the count asserted to be zero
+
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct AllocationTrap;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static ARMED: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for AllocationTrap {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if ARMED.with(Cell::get) {
+            let _ = ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: AllocationTrap = AllocationTrap;
+
+fn without_allocating<F: FnOnce()>(f: F) -> usize {
+    ALLOCATIONS.store(0, Ordering::SeqCst);
+    ARMED.with(|armed| armed.set(true));
+    f();
+    ARMED.with(|armed| armed.set(false));
+    ALLOCATIONS.load(Ordering::SeqCst)
+}
+
+#[test]
+fn core_controller_does_not_allocate() {
+    let now = || 100;
+    let mut handle_on = || Ok(());
+    let mut handle_off = || Ok(());
+
+    let allocations = without_allocating(|| {
+        let mut on_off = TimeConstrainedOnOff::new(
+            false,
+            Some(&mut handle_on),
+            Some(&mut handle_off),
+            Some(Duration::from_millis(10)),
+            Some(Duration::from_millis(5)),
+            &now,
+        );
+        on_off.set_maximum_on(Some(Duration::from_millis(50)));
+
+        for at in (110..300).step_by(7) {
+            let _ = on_off.bang_at(at);
+            let _ = on_off.poll_at(at);
+        }
+
+        let _ = on_off.is_on();
+        let _ = on_off.time_remaining();
+        let _ = on_off.remaining();
+        let _ = on_off.pack_status();
+        let _ = on_off.transitions_within(Duration::from_millis(100));
+        let _ = on_off.on_ratio_over(Duration::from_millis(100));
+        let _ = on_off.duty_since_checkpoint();
+        let _ = on_off.last_error();
+        let _ = on_off.config();
+        let _ = on_off.check_invariants();
+        let _ = on_off.simulate_bang(400);
+        let _ = on_off.next_allowed_at();
+
+        #[cfg(feature = "heapless")]
+        {
+            let history = on_off.history_vec::<4>();
+            assert_eq!(history.len(), 4);
+        }
+    });
+
+    assert_eq!(allocations, 0);
+}
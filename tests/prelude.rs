@@ -0,0 +1,40 @@
+use bangbang_timed::prelude::*;
+
+#[test]
+fn core_items_reachable() {
+    let now = || 0;
+
+    let mut hysteresis = HysteresisOnOff::new(
+        TimeConstrainedOnOff::new(false, None, None, None, None, &now)
+            .with_clock_policy(ClockPolicy::Wrapping),
+        1.0,
+        2.0,
+    );
+    assert!(hysteresis.update(0.0).is_ok());
+    assert_eq!(hysteresis.controller().state(), BangBangState::B);
+
+    let controller = hysteresis.controller_mut();
+    assert!(controller.apply(Command::Off).is_ok());
+    assert_eq!(
+        controller.simulate_bang(0),
+        SimResult::WouldTransition(BangBangState::B)
+    );
+    assert_eq!(soonest_allowed(&[controller]), None);
+    assert_eq!(Decision::Wait, Decision::Wait);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn std_items_reachable() {
+    let mut on_off = TimeConstrainedOnOff::new_std(
+        true,
+        None,
+        None,
+        Some(core::time::Duration::from_secs(60)),
+        None,
+    );
+
+    assert!(now_ms() < 60_000);
+    assert!(on_off.bang().is_err());
+    assert_eq!(on_off.is_on(), true);
+}
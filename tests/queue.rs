@@ -0,0 +1,101 @@
+#![cfg(feature = "heapless")]
+
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn drains_commands_in_order() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    let mut queue = CommandQueue::<4>::new();
+
+    assert!(queue.enqueue(Command::On).is_ok());
+    assert!(queue.enqueue(Command::Toggle).is_ok());
+    assert!(queue.enqueue(Command::On).is_ok());
+    assert_eq!(queue.enqueue(Command::Off), Err(Command::Off));
+    assert_eq!(queue.len(), 3);
+
+    assert!(queue.drain_into(&mut on_off).is_ok());
+    assert_eq!(queue.is_empty(), true);
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn drain_stops_at_blocked_command() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let faux_ten_milliseconds = Duration::from_millis(10);
+
+    let mut on_off =
+        TimeConstrainedOnOff::new(false, None, None, None, Some(faux_ten_milliseconds), &now);
+    let mut queue = CommandQueue::<8>::new();
+
+    assert!(queue.enqueue(Command::On).is_ok());
+    assert!(queue.enqueue(Command::Off).is_ok());
+
+    assert!(queue.drain_into(&mut on_off).is_err());
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(queue.len(), 2);
+
+    *faux_clock.lock().unwrap() = now() + 10;
+
+    assert!(queue.drain_into(&mut on_off).is_ok());
+    assert_eq!(on_off.is_off(), true);
+    assert_eq!(queue.is_empty(), true);
+}
+
+#[test]
+fn split_queue_fills_and_drains_from_separate_halves() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+    let mut queue = CommandQueue::<4>::new();
+    let (mut producer, mut consumer) = queue.split();
+
+    // the producer would live in an interrupt handler
+    std::thread::scope(|scope| {
+        let _ = scope.spawn(|| {
+            assert!(producer.enqueue(Command::On).is_ok());
+            assert!(producer.enqueue(Command::Toggle).is_ok());
+            assert!(producer.enqueue(Command::Toggle).is_ok());
+            assert_eq!(producer.ready(), false);
+        });
+    });
+
+    assert_eq!(consumer.len(), 3);
+    assert!(consumer.drain_into(&mut on_off).is_ok());
+    assert_eq!(consumer.is_empty(), true);
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn drain_drops_vetoed_command() {
+    let now = || 0;
+
+    let mut handle_on = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::A,
+            to: BangBangState::B,
+            code: 7,
+        })
+    };
+    let mut on_off = TimeConstrainedOnOff::new(false, Some(&mut handle_on), None, None, None, &now);
+    let mut queue = CommandQueue::<4>::new();
+
+    assert!(queue.enqueue(Command::On).is_ok());
+    assert!(queue.enqueue(Command::Off).is_ok());
+
+    assert!(matches!(
+        queue.drain_into(&mut on_off),
+        Err(TransitionError::Vetoed(_))
+    ));
+    assert_eq!(queue.len(), 1);
+
+    // the queue moves on instead of retrying the vetoed command forever
+    assert!(queue.drain_into(&mut on_off).is_ok());
+    assert_eq!(queue.is_empty(), true);
+    assert_eq!(on_off.is_off(), true);
+}
@@ -0,0 +1,48 @@
+use bangbang_timed::prelude::*;
+use core::sync::atomic::AtomicBool;
+use std::sync::mpsc;
+use std::thread;
+
+static MIRROR: AtomicBool = AtomicBool::new(false);
+
+#[test]
+fn readers_follow_writer_across_threads() {
+    let now = || 0;
+
+    let mut on_off =
+        TimeConstrainedOnOff::new(true, None, None, None, None, &now).with_state_mirror(&MIRROR);
+    let reader = on_off.split().unwrap();
+    assert_eq!(reader.is_on(), true);
+
+    let (to_reader, from_writer) = mpsc::channel::<bool>();
+    let (to_writer, from_reader) = mpsc::channel::<bool>();
+    thread::scope(|scope| {
+        let _ = scope.spawn(move || {
+            // each message announces the state the writer just published
+            for expected_on in from_writer {
+                assert_eq!(reader.is_on(), expected_on);
+                assert_eq!(reader.state() == BangBangState::B, expected_on);
+                to_writer.send(true).unwrap();
+            }
+        });
+
+        for _ in 0..10 {
+            assert!(on_off.bang().is_ok());
+            to_reader.send(on_off.is_on()).unwrap();
+            assert!(from_reader.recv().unwrap());
+        }
+        drop(to_reader);
+    });
+
+    // the writer keeps full access while readers are alive
+    assert!(on_off.reset_to(BangBangState::A, false).is_ok());
+    assert_eq!(reader.is_off(), true);
+}
+
+#[test]
+fn split_without_mirror() {
+    let now = || 0;
+
+    let on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    assert!(on_off.split().is_none());
+}
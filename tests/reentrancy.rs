@@ -0,0 +1,5 @@
+#[test]
+fn handlers_cannot_reenter_controller() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/reentrant_*.rs");
+}
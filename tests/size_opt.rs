@@ -0,0 +1,31 @@
+//! exercised by `cargo test --no-default-features`, the configuration recommended for the
+//! smallest footprint, to make sure it builds and behaves the same without any logging
+#![cfg(not(feature = "log"))]
+
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+
+#[test]
+fn operates_without_logging() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_secs(60 * 24 * 60 * 60)),
+        &now,
+    )
+    .with_stall_detection(1)
+    .with_advisory_handlers();
+
+    assert!(on_off.bang_at(9).is_err());
+    assert!(on_off.bang_at(9).is_err());
+    assert_eq!(on_off.clock_stalled(), true);
+    assert!(on_off.bang_at(10).is_ok());
+
+    on_off.suspend_constraints();
+    assert!(on_off.bang_at(10).is_ok());
+    assert_eq!(on_off.is_on(), true);
+}
@@ -0,0 +1,96 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+
+#[test]
+fn periodic_toggle_produces_constrained_square_wave() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(25)),
+        None,
+        &now,
+    );
+
+    let mut clock = 0;
+    let tick = move || {
+        let now = clock;
+        clock += 10;
+        now
+    };
+
+    // `Some(on)` for each transition and `None` for each blocked toggle
+    let wave: Vec<(u32, Option<bool>)> = on_off
+        .run(PeriodicToggle::new(Duration::from_millis(10)), tick, 9)
+        .map(|(now, report)| match report {
+            StepReport::Transitioned(state) => (now, Some(state == BangBangState::B)),
+            StepReport::Blocked { .. } => (now, None),
+            _ => panic!("unexpected {:?} at {}ms", report, now),
+        })
+        .collect();
+
+    // the toggles 10ms and 20ms after turning on are blocked by the minimum on time, so the
+    // square wave is on for 30ms and off for 10ms
+    assert_eq!(
+        wave,
+        vec![
+            (0, Some(true)),
+            (10, None),
+            (20, None),
+            (30, Some(false)),
+            (40, Some(true)),
+            (50, None),
+            (60, None),
+            (70, Some(false)),
+            (80, Some(true)),
+        ]
+    );
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn replays_recorded_trace() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(5)),
+        &now,
+    );
+
+    let trace = [
+        (5, Command::On),
+        (8, Command::Off),
+        (15, Command::Toggle),
+        (17, Command::On),
+        (20, Command::On),
+    ];
+    let outcomes: Vec<(u32, bool, Option<TransitionError>)> = Replay::new(&mut on_off, &trace)
+        .map(|(now, state, result)| (now, state == BangBangState::B, result.err()))
+        .collect();
+
+    assert_eq!(outcomes.len(), trace.len());
+    let summary: Vec<(u32, bool, bool)> = outcomes
+        .iter()
+        .map(|(now, on, error)| (*now, *on, error.is_some()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (5, true, false),
+            (8, true, true),
+            (15, false, false),
+            (17, false, true),
+            (20, true, false),
+        ]
+    );
+    assert_eq!(
+        outcomes[1].2.and_then(|error| error.remaining()),
+        Some(Duration::from_millis(7))
+    );
+}
@@ -0,0 +1,43 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn second_stage_waits_for_first_stage_minimum() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+
+    let mut stage_1 = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    let mut stage_2 = TimeConstrainedOnOff::new(false, None, None, None, None, &now);
+
+    // refused while the first stage is off
+    assert_eq!(stage_2.link_enable(&stage_1), false);
+    match stage_2.set_linked(&stage_1, BangBangState::B) {
+        Err(TransitionError::UpstreamNotReady { .. }) => {}
+        _ => panic!("expected the second stage to be held back"),
+    }
+
+    // refused while the first stage is within its minimum on time
+    assert!(stage_1.set(BangBangState::B).is_ok());
+    *faux_clock.lock().unwrap() = 9;
+    assert!(stage_2.set_linked(&stage_1, BangBangState::B).is_err());
+    assert_eq!(stage_2.is_off(), true);
+
+    *faux_clock.lock().unwrap() = 10;
+    assert_eq!(stage_2.link_enable(&stage_1), true);
+    assert!(stage_2.set_linked(&stage_1, BangBangState::B).is_ok());
+    assert_eq!(stage_2.is_on(), true);
+
+    // turning off is never held back by the first stage
+    assert!(stage_1.set(BangBangState::A).is_ok());
+    assert!(stage_2.set_linked(&stage_1, BangBangState::A).is_ok());
+    assert_eq!(stage_2.is_off(), true);
+}
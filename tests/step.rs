@@ -0,0 +1,127 @@
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+
+#[test]
+fn step_reports_each_outcome() {
+    let now = || 0;
+
+    let mut handle_on = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::A,
+            to: BangBangState::B,
+            code: 7,
+        })
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        Some(&mut handle_on),
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(5)),
+        &now,
+    );
+    on_off.set_maximum_on(Some(Duration::from_millis(50)));
+
+    assert!(matches!(on_off.step(3, None), StepReport::NoCommand));
+    assert!(matches!(
+        on_off.step(4, Some(Command::Off)),
+        StepReport::Blocked { remaining } if remaining == Duration::from_millis(6)
+    ));
+    assert!(matches!(
+        on_off.step(10, Some(Command::Toggle)),
+        StepReport::Transitioned(BangBangState::A)
+    ));
+    assert!(matches!(
+        on_off.step(20, Some(Command::On)),
+        StepReport::Failed(TransitionError::Vetoed(_))
+    ));
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+fn step_forces_maximum_before_command() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+    on_off.set_maximum_on(Some(Duration::from_millis(10)));
+
+    assert!(matches!(
+        on_off.step(9, Some(Command::On)),
+        StepReport::Transitioned(BangBangState::B)
+    ));
+    // the command is discarded in favour of the forced transition
+    assert!(matches!(
+        on_off.step(19, Some(Command::On)),
+        StepReport::Forced(BangBangState::A)
+    ));
+    assert_eq!(on_off.is_off(), true);
+
+    assert!(matches!(
+        on_off.step(20, Some(Command::On)),
+        StepReport::Transitioned(BangBangState::B)
+    ));
+}
+
+#[test]
+fn tick_retries_pending_command_until_permitted() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        false,
+        None,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        &now,
+    );
+
+    assert!(matches!(on_off.tick(2), StepReport::NoCommand));
+
+    on_off.request(Command::On);
+    assert!(matches!(
+        on_off.tick(4),
+        StepReport::Blocked { remaining } if remaining == Duration::from_millis(6)
+    ));
+    assert_eq!(on_off.pending_command(), Some(Command::On));
+    assert_eq!(on_off.is_off(), true);
+
+    assert!(matches!(
+        on_off.tick(10),
+        StepReport::Transitioned(BangBangState::B)
+    ));
+    assert_eq!(on_off.pending_command(), None);
+    assert!(matches!(on_off.tick(11), StepReport::NoCommand));
+    assert_eq!(on_off.is_on(), true);
+}
+
+#[test]
+fn step_reports_veto_of_forced_transition() {
+    let now = || 0;
+
+    let mut handle_off = || {
+        Err(BangBangError::StateChangeTemporarilyConstrained {
+            from: BangBangState::B,
+            to: BangBangState::A,
+            code: 7,
+        })
+    };
+
+    let mut on_off = TimeConstrainedOnOff::new(true, None, Some(&mut handle_off), None, None, &now);
+    on_off.set_maximum_on(Some(Duration::from_millis(10)));
+
+    match on_off.step(10, Some(Command::On)) {
+        StepReport::Failed(error) => {
+            assert_eq!(error.kind(), ErrorKind::Vetoed);
+            assert!(matches!(
+                error,
+                TransitionError::Vetoed(BangBangError::StateChangeTemporarilyConstrained {
+                    code: 7,
+                    ..
+                })
+            ));
+        }
+        _ => panic!("expected the forced transition to be vetoed"),
+    }
+    assert_eq!(on_off.is_on(), true);
+}
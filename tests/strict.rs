@@ -0,0 +1,49 @@
+#![cfg(feature = "debug-strict")]
+
+use bangbang_timed::prelude::*;
+use core::time::Duration;
+
+#[test]
+fn strict_mode_permits_normal_and_forced_transitions() {
+    let now = || 0;
+    let mut force = |_: &TimeConstrainedOnOff<'_>| Decision::Force;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        Some(Duration::from_millis(10)),
+        &now,
+    );
+
+    assert!(on_off.bang_at(5).is_err());
+    assert!(on_off.bang_at(10).is_ok());
+    assert!(on_off.force_toggle().is_ok());
+    assert_eq!(on_off.is_on(), true);
+
+    // a fallback forcing the transition is exempt
+    let mut on_off = on_off.with_fallback(&mut force);
+    assert!(on_off.bang_at(11).is_ok());
+    assert_eq!(on_off.is_off(), true);
+}
+
+#[test]
+#[should_panic(expected = "bypassed an active minimum")]
+fn strict_mode_catches_active_time_beyond_elapsed_time() {
+    let now = || 0;
+
+    let mut on_off = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    )
+    .with_gated_time();
+
+    // credits more active time than the clock shows has passed
+    on_off.add_active_time(Duration::from_millis(10));
+    let _ = on_off.bang_at(0);
+}
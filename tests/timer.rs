@@ -0,0 +1,65 @@
+#![cfg(feature = "async")]
+
+use bangbang_timed::prelude::*;
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use core::time::Duration;
+use std::sync::{Arc, Mutex};
+
+/// timer that completes immediately after advancing the faux clock by the requested duration
+struct FauxTimer {
+    clock: Arc<Mutex<u32>>,
+    requested: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl Timer for FauxTimer {
+    async fn sleep(&self, duration: Duration) {
+        self.requested.lock().unwrap().push(duration);
+        *self.clock.lock().unwrap() += duration.as_millis() as u32;
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn bang_when_ready_sleeps_for_remaining_time() {
+    let faux_clock = Arc::new(Mutex::new(0 as u32));
+    let faux_clock_inner = Arc::clone(&faux_clock);
+    let now = move || faux_clock_inner.lock().unwrap().clone();
+    let requested = Arc::new(Mutex::new(Vec::new()));
+
+    let controller = TimeConstrainedOnOff::new(
+        true,
+        None,
+        None,
+        Some(Duration::from_millis(10)),
+        None,
+        &now,
+    );
+    let timer = FauxTimer {
+        clock: Arc::clone(&faux_clock),
+        requested: Arc::clone(&requested),
+    };
+    let mut on_off = AsyncOnOff::new(controller, timer);
+
+    *faux_clock.lock().unwrap() = 3;
+    assert!(block_on(on_off.bang_when_ready()).is_ok());
+    assert_eq!(on_off.controller().is_off(), true);
+    assert_eq!(*faux_clock.lock().unwrap(), 10);
+
+    // unconstrained in the off state, so no sleep is needed
+    assert!(block_on(on_off.bang_when_ready()).is_ok());
+    assert_eq!(on_off.controller().is_on(), true);
+
+    assert_eq!(*requested.lock().unwrap(), vec![Duration::from_millis(7)]);
+}
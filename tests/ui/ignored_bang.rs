@@ -0,0 +1,12 @@
+// warnings are denied so that the compiler rejects the ignored results outright
+#![deny(unused_must_use)]
+
+use bangbang_timed::prelude::*;
+
+fn main() {
+    let now = || 0;
+    let mut on_off = TimeConstrainedOnOff::new(true, None, None, None, None, &now);
+
+    on_off.bang();
+    on_off.time_remaining();
+}
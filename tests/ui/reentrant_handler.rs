@@ -0,0 +1,19 @@
+// a handler cannot reach the controller it is installed on, so it cannot call back into it
+use bangbang_timed::prelude::*;
+
+fn main() {
+    let now = || 0;
+    let mut slot: Option<TimeConstrainedOnOff<'_>> = None;
+
+    let mut handle_on = || slot.as_mut().map_or(Ok(()), |on_off| on_off.bang());
+    slot = Some(TimeConstrainedOnOff::new(
+        false,
+        Some(&mut handle_on),
+        None,
+        None,
+        None,
+        &now,
+    ));
+
+    let _ = slot.as_mut().map(|on_off| on_off.bang());
+}